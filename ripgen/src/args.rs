@@ -1,14 +1,32 @@
-use std::fs::read_to_string;
-use std::io::{BufRead, stdin};
-use clap::Parser;
-use anyhow::Result;
+use std::collections::HashSet;
+use std::fs::{canonicalize, File, read, read_dir, read_to_string};
+use std::io::{BufRead, BufReader, Read, stdin};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use clap::{ArgEnum, CommandFactory, ErrorKind, Parser, Subcommand};
+use anyhow::{anyhow, Result};
+use flate2::read::MultiGzDecoder;
+#[cfg(feature = "builtin-wordlists")]
+use ripgen_lib::wordlist::BUILTIN_WORDLISTS;
+use ripgen_lib::shard::Shard;
+use ripgen_lib::wordlist::WordEntry;
+#[cfg(feature = "resolve")]
+use std::net::{IpAddr, SocketAddr};
+#[cfg(feature = "resolve")]
+use std::time::Duration;
+#[cfg(feature = "resolve")]
+use ripgen_lib::resolve::{Resolver, ResolverConfig};
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about)]
 pub struct Args {
+    #[clap(subcommand)]
+    pub(crate) command: Option<Command>,
+
     #[clap(
         short = 'd',
         long = "domains",
+        global = true,
         help = "The file containing domains you want to generate permutations from. If this is not specified, domains are read from stdin."
     )]
     pub(crate) domain_file_path: Option<String>,
@@ -16,26 +34,640 @@ pub struct Args {
     #[clap(
         short = 'w',
         long = "wordlist",
-        help = "The supplementary wordlist file to include."
+        global = true,
+        multiple_occurrences = true,
+        help = "A supplementary wordlist to include: a file, gzip-compressed or not, a directory of them, or a built-in list: `builtin:common`, `builtin:env`, `builtin:cloud` or `builtin:dnsgen`. Can be specified multiple times. Each line holds a word, optionally followed by a weight and `key=value` tags (e.g. `admin 0.9 env=any`)."
+    )]
+    pub(crate) wordlists: Vec<String>,
+
+    #[clap(
+        long = "word-tag",
+        global = true,
+        multiple_occurrences = true,
+        help = "Only use the wordlist entries with this `key=value` tag. Entries with any of the given tags are used if specified multiple times."
     )]
-    pub(crate) wordlist: Option<String>,
+    pub(crate) word_tags: Vec<String>,
+
+    #[clap(
+        long = "min-word-weight",
+        global = true,
+        help = "Only use the wordlist entries with at least this weight. Entries without a weight have a weight of 1."
+    )]
+    pub(crate) min_word_weight: Option<f64>,
 
     #[clap(
         short = 'l',
         long = "len",
+        global = true,
         help = "The minimum length for a word to be considered important. If not specified, all words are accepted."
     )]
     pub(crate) min_word_len: Option<usize>,
 
+    #[clap(
+        long = "scope-words-by-root",
+        help = "Only permute each domain with the words from domains under the same root, plus the wordlist. Scoping words by arbitrary groups of domains is only available from the library."
+    )]
+    pub(crate) scope_words_by_root: bool,
+
+    #[clap(
+        long = "words-for",
+        multiple_occurrences = true,
+        conflicts_with = "scope-words-by-root",
+        help = "Restrict the words a single transform uses, as `TRANSFORM:FILTER,...` (e.g. `dash:max-len=4` or `permute:tag=kind=service,wordlist-only`). The filters are min-len=N, max-len=N, tag=KEY=VALUE for wordlist entries with any of the given tags, and wordlist-only to leave out the words extracted from the input domains. The transforms are substring-swap, token-swap, permute, depth-permute, dash, altdns-insert, altdns-dash and altdns-join, and only the ones the chosen options run can be restricted. Can be specified multiple times, once per transform. Can't be combined with --scope-words-by-root."
+    )]
+    pub(crate) words_for: Vec<TransformWords>,
+
     #[clap(
         short = 'f',
         long = "fast",
-        help = "Uses the most likely words only in permutations"
+        help = "Uses the most likely words only in permutations, i.e. the ones with the highest weight."
+    )]
+    pub(crate) fast: Option<bool>,
+
+    #[clap(
+        long = "profile",
+        arg_enum,
+        default_value = "dnsgen",
+        help = "The set of transforms to run. The altdns profile reproduces altdns's output: it only uses the wordlist, and writes each name once and none of the input names. The infer profile learns naming patterns from the input domains and only generates names that fit them. Both ignore the other transform options."
+    )]
+    pub(crate) profile: Profile,
+
+    #[clap(
+        long = "add-number-suffix",
+        help = "With --profile altdns, also append the digits 0-9 to every subdomain label, like altdns's --add-number-suffix."
+    )]
+    pub(crate) add_number_suffix: bool,
+
+    #[clap(
+        long = "swap-root",
+        multiple_occurrences = true,
+        help = "An alternate root (e.g. example.net) to move every subdomain onto. Can be specified multiple times."
+    )]
+    pub(crate) swap_roots: Vec<String>,
+
+    #[clap(
+        long = "swap-all-roots",
+        help = "Move every subdomain onto every other root seen in the input domains."
+    )]
+    pub(crate) swap_all_roots: bool,
+
+    #[clap(
+        long = "swap-suffix",
+        multiple_occurrences = true,
+        help = "An alternate public suffix (e.g. io, co.uk) to try every domain under. Can be specified multiple times."
+    )]
+    pub(crate) swap_suffixes: Vec<String>,
+
+    #[clap(
+        long = "delete-labels",
+        help = "Also generate names with one subdomain label removed at a time."
+    )]
+    pub(crate) delete_labels: bool,
+
+    #[clap(
+        long = "reorder-labels",
+        help = "Also generate names with adjacent subdomain labels swapped or a label moved to the front."
+    )]
+    pub(crate) reorder_labels: bool,
+
+    #[clap(
+        long = "substring-swap",
+        help = "Use dnsgen's substring word swapping, which replaces every occurrence of a word anywhere in the name, instead of replacing whole tokens one at a time."
+    )]
+    pub(crate) substring_swap: bool,
+
+    #[clap(
+        long = "vary-separators",
+        help = "Also generate names with dashes removed or promoted to label boundaries, and with adjacent labels joined."
+    )]
+    pub(crate) vary_separators: bool,
+
+    #[clap(
+        long = "typo",
+        help = "Also generate typosquatting variants (omission, repetition, adjacent keys, transposition, bit-flips, vowel swaps, hyphenation and homoglyphs) of each registrable name."
+    )]
+    pub(crate) typo: bool,
+
+    #[clap(
+        long = "depth",
+        default_value = "1",
+        help = "The number of words to insert into each domain, one after another."
+    )]
+    pub(crate) depth: usize,
+
+    #[clap(
+        long = "depth-numbers",
+        default_value = "0",
+        help = "Also insert each word with the numbers in it increased and decreased by up to this amount."
+    )]
+    pub(crate) depth_numbers: u64,
+
+    #[clap(
+        long = "depth-cap",
+        help = "The maximum number of inserted-word permutations to generate for a single domain. Defaults to 100000 from a --depth of 2, since every permutation of a domain is held in memory."
+    )]
+    pub(crate) depth_cap: Option<usize>,
+
+    #[clap(
+        long = "infer-limit",
+        default_value = "10000",
+        help = "The maximum number of names to generate from each learned pattern in the infer profile."
+    )]
+    pub(crate) infer_limit: usize,
+
+    #[clap(
+        long = "markov",
+        help = "Also generate labels from an n-gram model trained on the input subdomain labels."
+    )]
+    pub(crate) markov: bool,
+
+    #[clap(
+        long = "markov-corpus",
+        requires = "markov",
+        help = "A file of additional names, one per line, to train the n-gram model on. Requires --markov."
+    )]
+    pub(crate) markov_corpus: Option<String>,
+
+    #[clap(
+        long = "markov-order",
+        default_value = "3",
+        help = "The number of characters (or tokens) in each n-gram."
+    )]
+    pub(crate) markov_order: usize,
+
+    #[clap(
+        long = "markov-tokens",
+        help = "Train the n-gram model on tokens split on dashes and digits instead of characters."
+    )]
+    pub(crate) markov_tokens: bool,
+
+    #[clap(
+        long = "markov-limit",
+        default_value = "1000",
+        help = "The maximum number of labels to generate from the n-gram model."
+    )]
+    pub(crate) markov_limit: usize,
+
+    #[clap(
+        long = "markov-min-probability",
+        default_value = "0.0001",
+        help = "Labels the n-gram model considers less likely than this are not generated."
+    )]
+    pub(crate) markov_min_probability: f64,
+
+    #[clap(
+        long = "markov-seed",
+        help = "Randomly sample labels from the n-gram model with this seed instead of taking the most likely ones."
+    )]
+    pub(crate) markov_seed: Option<u64>,
+
+    #[clap(
+        long = "ranked",
+        help = "Only output this many of the most likely names, from most to least likely. Memory use grows with this number."
+    )]
+    pub(crate) ranked: Option<usize>,
+
+    #[clap(
+        long = "provenance",
+        help = "Append a tab and the name of the transform that produced each name to the output. Can't be combined with --format massdns."
+    )]
+    pub(crate) provenance: bool,
+
+    #[clap(
+        long = "shard",
+        help = "Only generate one slice of the output, as `index/count` with index from 1 to count. Workers given the same input and different indexes produce disjoint output that adds up to the whole."
+    )]
+    pub(crate) shard: Option<Shard>,
+
+    #[clap(
+        long = "checkpoint",
+        conflicts_with = "ranked",
+        help = "Periodically record how far generation got in this file. Can't be combined with --ranked, --rounds above 1 or the resolve subcommand."
+    )]
+    pub(crate) checkpoint: Option<String>,
+
+    #[clap(
+        long = "checkpoint-every",
+        default_value = "100000",
+        help = "How many names to output between checkpoints."
+    )]
+    pub(crate) checkpoint_every: usize,
+
+    #[clap(
+        long = "resume",
+        requires = "checkpoint",
+        help = "Continue from the position recorded in the --checkpoint file, without repeating any earlier output."
+    )]
+    pub(crate) resume: bool,
+
+    #[clap(
+        long = "format",
+        arg_enum,
+        default_value = "plain",
+        help = "How each name is written. massdns writes `name. A` query lines."
+    )]
+    pub(crate) format: OutputFormat,
+
+    #[clap(
+        long = "trailing-dot",
+        help = "Append a trailing dot to each name."
+    )]
+    pub(crate) trailing_dot: bool,
+
+    #[clap(
+        short = 'o',
+        long = "output-dir",
+        help = "Write the output to files in this directory instead of stdout."
+    )]
+    pub(crate) output_dir: Option<String>,
+
+    #[clap(
+        long = "split-by-root",
+        requires = "output-dir",
+        help = "Write the names of each root domain to their own file, named after the root. Requires --output-dir."
+    )]
+    pub(crate) split_by_root: bool,
+
+    #[clap(
+        long = "output-shards",
+        requires = "output-dir",
+        help = "Split the output into this many files of about the same size. Requires --output-dir."
+    )]
+    pub(crate) output_shards: Option<usize>,
+
+    #[clap(
+        long = "model",
+        global = true,
+        help = "A feedback model file written by `ripgen learn`. Transforms that produced confirmed names are ranked higher."
+    )]
+    pub(crate) model: Option<String>,
+
+    #[clap(
+        long = "prune",
+        help = "Skip transforms that have not produced any confirmed names according to the feedback model."
+    )]
+    pub(crate) prune: bool,
+
+    #[clap(
+        long = "prune-words",
+        help = "Skip words that have not been part of any confirmed names according to the feedback model."
+    )]
+    pub(crate) prune_words: bool,
+
+    #[clap(
+        long = "rounds",
+        default_value = "1",
+        help = "The number of generation rounds. Between rounds, confirmed names are read from --confirmed-from and added to the domains, and only names not output in earlier rounds are output."
+    )]
+    pub(crate) rounds: usize,
+
+    #[clap(
+        long = "confirmed-from",
+        help = "Where to read confirmed names from between rounds. A path is read in full before every round. `fd:N` reads from file descriptor N until an empty line or the end of input."
     )]
-    pub(crate) fast: Option<bool>
+    pub(crate) confirmed_from: Option<String>
+}
+
+/// The default `--depth-cap` from a depth of 2, where the permutations of a single domain quickly
+/// outgrow memory.
+const DEFAULT_DEEP_DEPTH_CAP: usize = 100_000;
+
+/// The transforms that use words, by the name they're given in provenance and the feedback model.
+const WORD_TRANSFORMS: &[&str] = &["substring-swap", "token-swap", "permute", "depth-permute", "dash", "altdns-insert", "altdns-dash", "altdns-join"];
+
+/// Restricts the words of a single transform, see `--words-for`.
+#[derive(Clone, Debug, Default)]
+pub struct TransformWords {
+    pub(crate) transform: String,
+    pub(crate) min_len: Option<usize>,
+    pub(crate) max_len: Option<usize>,
+    pub(crate) tags: Vec<(String, String)>,
+    pub(crate) wordlist_only: bool
+}
+
+impl TransformWords {
+    /// Returns whether the restriction only allows words from the wordlist.
+    pub(crate) fn needs_wordlist(&self) -> bool {
+        self.wordlist_only || !self.tags.is_empty()
+    }
+
+    pub(crate) fn accepts_len(&self, word: &str) -> bool {
+        self.min_len.is_none_or(|min_len| word.len() >= min_len)
+            && self.max_len.is_none_or(|max_len| word.len() <= max_len)
+    }
+
+    /// Returns whether a wordlist entry has any of the required tags.
+    pub(crate) fn accepts_entry(&self, entry: &WordEntry) -> bool {
+        self.tags.is_empty() || self.tags.iter().any(|(key, value)| entry.has_tag(key, value))
+    }
+}
+
+impl FromStr for TransformWords {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (transform, filters) = s.split_once(':').unwrap_or((s, ""));
+
+        if !WORD_TRANSFORMS.contains(&transform) {
+            return Err(format!("Unknown transform {}, expected one of {}", transform, WORD_TRANSFORMS.join(", ")));
+        }
+
+        let mut words = TransformWords {
+            transform: transform.to_string(),
+            ..TransformWords::default()
+        };
+
+        let invalid = |filter: &str| format!("Invalid word filter {}", filter);
+
+        for filter in filters.split(',').filter(|filter| !filter.is_empty()) {
+            match filter.split_once('=') {
+                Some(("min-len", len)) => words.min_len = Some(len.parse().map_err(|_| invalid(filter))?),
+                Some(("max-len", len)) => words.max_len = Some(len.parse().map_err(|_| invalid(filter))?),
+                Some(("tag", tag)) => {
+                    let (key, value) = tag.split_once('=').ok_or_else(|| invalid(filter))?;
+                    words.tags.push((key.to_string(), value.to_string()));
+                },
+                None if filter == "wordlist-only" => words.wordlist_only = true,
+                _ => return Err(invalid(filter))
+            }
+        }
+
+        Ok(words)
+    }
+}
+
+/// Where confirmed names are read from between rounds.
+pub enum ConfirmedSource {
+    Path(String),
+    Reader(BufReader<File>)
+}
+
+impl ConfirmedSource {
+    /// Reads the names confirmed during the last round. Anything after a tab on a line is ignored
+    /// so output from --provenance can be passed back in directly.
+    pub(crate) fn read_round(&mut self) -> Result<Vec<String>> {
+        let lines: Vec<String> = match self {
+            ConfirmedSource::Path(path) => read_to_string(path)?.lines().map(str::to_string).collect(),
+            ConfirmedSource::Reader(reader) => {
+                let mut lines = Vec::new();
+
+                for line in reader.lines() {
+                    let line = line?;
+
+                    if line.trim().is_empty() {
+                        break;
+                    }
+
+                    lines.push(line);
+                }
+
+                lines
+            }
+        };
+
+        let names = lines
+            .iter()
+            .filter_map(|line| line.split('\t').next())
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect();
+
+        Ok(names)
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Updates the feedback model (--model) with the generated names that were confirmed to resolve.
+    Learn {
+        #[clap(
+            long = "confirmed",
+            help = "The file of confirmed names, one per line. Lines may include the transform as output with --provenance."
+        )]
+        confirmed: String
+    },
+    /// Resolves the generated names and only outputs the ones with A, AAAA or CNAME records. With
+    /// --rounds, the names that resolved are used as the confirmed names for the next round.
+    #[cfg(feature = "resolve")]
+    Resolve {
+        #[clap(
+            short = 'r',
+            long = "resolver",
+            multiple_occurrences = true,
+            use_value_delimiter = true,
+            help = "A nameserver to query, as `ip` or `ip:port`. Defaults to 1.1.1.1, 8.8.8.8 and 9.9.9.9."
+        )]
+        resolvers: Vec<String>,
+
+        #[clap(
+            long = "concurrency",
+            default_value = "500",
+            help = "The maximum number of names being resolved at once."
+        )]
+        concurrency: usize,
+
+        #[clap(
+            long = "rate-limit",
+            help = "The maximum number of names to start resolving per second."
+        )]
+        rate_limit: Option<u32>,
+
+        #[clap(
+            long = "retries",
+            default_value = "2",
+            help = "How many more times a query is sent after a timeout or failure."
+        )]
+        retries: usize,
+
+        #[clap(
+            long = "timeout",
+            default_value = "2000",
+            help = "How long to wait for each response, in milliseconds."
+        )]
+        timeout_ms: u64,
+
+        #[clap(
+            long = "keep-wildcards",
+            help = "Output names even when they only resolve because of a wildcard record in their parent zone."
+        )]
+        keep_wildcards: bool,
+
+        #[clap(
+            long = "wildcard-probes",
+            default_value = "3",
+            help = "How many random labels are resolved under each parent zone to detect wildcard records."
+        )]
+        wildcard_probes: usize
+    }
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Plain,
+    Massdns
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Profile {
+    Dnsgen,
+    Altdns,
+    Infer
+}
+
+impl Profile {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Profile::Dnsgen => "dnsgen",
+            Profile::Altdns => "altdns",
+            Profile::Infer => "infer"
+        }
+    }
+
+    /// Returns whether words extracted from the input domains are used alongside the wordlist.
+    pub(crate) fn uses_domain_words(&self) -> bool {
+        match self {
+            Profile::Dnsgen => true,
+            Profile::Altdns | Profile::Infer => false
+        }
+    }
 }
 
 impl Args {
+    /// Parses the arguments like [Parser::parse], and also exits with a usage error on combinations
+    /// that depend on argument values or the subcommand, which clap can't check on its own.
+    pub(crate) fn parse_checked() -> Self {
+        let args = Self::parse();
+
+        // names resolved by the resolve subcommand stand in for --confirmed-from between rounds
+        if args.rounds > 1 && args.confirmed_from.is_none() && !args.is_resolving() {
+            Self::command()
+                .error(ErrorKind::MissingRequiredArgument, "--rounds above 1 requires --confirmed-from or the resolve subcommand.")
+                .exit();
+        }
+
+        // anything buffered or reordered before it's written can't be accounted for by a checkpoint
+        if args.checkpoint.is_some() && (args.rounds > 1 || args.is_resolving()) {
+            Self::command()
+                .error(ErrorKind::ArgumentConflict, "--checkpoint can't be combined with --rounds above 1 or the resolve subcommand.")
+                .exit();
+        }
+
+        let mut restricted = HashSet::new();
+        for words in &args.words_for {
+            if !restricted.insert(words.transform.as_str()) {
+                Self::command()
+                    .error(ErrorKind::ArgumentConflict, format!("--words-for was given more than once for {}, combine its filters instead.", words.transform))
+                    .exit();
+            }
+
+            if !args.runs_transform(&words.transform) {
+                Self::command()
+                    .error(ErrorKind::ArgumentConflict, format!("--words-for restricts {}, which isn't run with these options.", words.transform))
+                    .exit();
+            }
+        }
+
+        // massdns reads every field of a line as part of the query
+        if args.provenance && args.format == OutputFormat::Massdns {
+            Self::command()
+                .error(ErrorKind::ArgumentConflict, "--provenance can't be combined with --format massdns.")
+                .exit();
+        }
+
+        args
+    }
+
+    /// Returns whether the word transform with the given name is run with the chosen profile and
+    /// transform options.
+    fn runs_transform(&self, transform: &str) -> bool {
+        match (self.profile, transform) {
+            (Profile::Altdns, "altdns-insert" | "altdns-dash" | "altdns-join") => true,
+            (Profile::Dnsgen, "substring-swap") => self.substring_swap,
+            (Profile::Dnsgen, "token-swap") => !self.substring_swap,
+            (Profile::Dnsgen, "permute") => !self.uses_depth_permutation(),
+            (Profile::Dnsgen, "depth-permute") => self.uses_depth_permutation(),
+            (Profile::Dnsgen, "dash") => true,
+            _ => false
+        }
+    }
+
+    /// Returns whether the `resolve` subcommand was used.
+    fn is_resolving(&self) -> bool {
+        #[cfg(feature = "resolve")]
+        if let Some(Command::Resolve { .. }) = self.command {
+            return true;
+        }
+
+        false
+    }
+
+    /// Returns the resolver configured by the `resolve` subcommand, if it was used.
+    #[cfg(feature = "resolve")]
+    pub(crate) fn resolver(&self) -> Result<Option<Resolver>> {
+        let (resolvers, mut config) = match self.command {
+            Some(Command::Resolve {
+                ref resolvers,
+                concurrency,
+                rate_limit,
+                retries,
+                timeout_ms,
+                keep_wildcards,
+                wildcard_probes
+            }) => (resolvers, ResolverConfig {
+                concurrency,
+                rate_limit,
+                retries,
+                timeout: Duration::from_millis(timeout_ms),
+                filter_wildcards: !keep_wildcards,
+                wildcard_probes,
+                ..ResolverConfig::default()
+            }),
+            _ => return Ok(None)
+        };
+
+        if !resolvers.is_empty() {
+            config.nameservers = resolvers
+                .iter()
+                .map(|resolver| match resolver.parse::<IpAddr>() {
+                    Ok(ip) => Ok(SocketAddr::new(ip, 53)),
+                    Err(_) => resolver.parse::<SocketAddr>()
+                })
+                .collect::<std::result::Result<_, _>>()?;
+        }
+
+        Ok(Some(Resolver::new(config)?))
+    }
+
+    pub(crate) fn confirmed_source(&self) -> Result<Option<ConfirmedSource>> {
+        let source = match self.confirmed_from {
+            Some(ref source) => match source.strip_prefix("fd:") {
+                Some(fd) => ConfirmedSource::Reader(BufReader::new(File::open(format!("/dev/fd/{fd}"))?)),
+                None => ConfirmedSource::Path(source.clone())
+            },
+            None => return Ok(None)
+        };
+
+        Ok(Some(source))
+    }
+
+    /// Returns the `--word-tag` tags split into their key and value.
+    pub(crate) fn word_tags(&self) -> Result<Vec<(&str, &str)>> {
+        self.word_tags
+            .iter()
+            .map(|tag| tag.split_once('=').ok_or_else(|| anyhow!("Expected a `key=value` tag: {}", tag)))
+            .collect()
+    }
+
+    /// Returns whether word insertion needs more than dnsgen's single-level permutation.
+    pub(crate) fn uses_depth_permutation(&self) -> bool {
+        self.depth != 1 || self.depth_numbers > 0 || self.depth_cap.is_some()
+    }
+
+    /// Returns the `--depth-cap`, or the default cap when inserting 2 or more words deep.
+    pub(crate) fn depth_cap(&self) -> Option<usize> {
+        self.depth_cap.or_else(|| (self.depth >= 2).then_some(DEFAULT_DEEP_DEPTH_CAP))
+    }
+
     pub(crate) fn get_domain_str(&self) -> Result<String> {
         let output = match self.domain_file_path {
             Some(ref path) => read_to_string(path)?,
@@ -51,12 +683,100 @@ impl Args {
         Ok(output)
     }
 
-    pub(crate) fn get_wordlist_str(&self) -> Result<String> {
-        let output = match self.wordlist {
+    pub(crate) fn get_markov_corpus_str(&self) -> Result<String> {
+        let output = match self.markov_corpus {
             Some(ref path) => read_to_string(path)?,
             None => String::new()
         };
 
         Ok(output)
     }
-}
\ No newline at end of file
+
+    pub(crate) fn get_wordlist_str(&self) -> Result<String> {
+        let wordlists = self.wordlists
+            .iter()
+            .map(|source| match source.strip_prefix(BUILTIN_PREFIX) {
+                Some(name) => read_builtin_wordlist(name),
+                None => read_wordlist_path(Path::new(source))
+            })
+            .collect::<Result<Vec<String>>>()?;
+
+        Ok(wordlists.join("\n"))
+    }
+}
+
+const BUILTIN_PREFIX: &str = "builtin:";
+
+#[cfg(feature = "builtin-wordlists")]
+fn read_builtin_wordlist(name: &str) -> Result<String> {
+    ripgen_lib::wordlist::builtin(name)
+        .map(str::to_string)
+        .ok_or_else(|| {
+            let names: Vec<String> = BUILTIN_WORDLISTS
+                .iter()
+                .map(|(name, _)| format!("{}{}", BUILTIN_PREFIX, name))
+                .collect();
+
+            anyhow!("Unknown built-in wordlist {}{}, expected one of {}", BUILTIN_PREFIX, name, names.join(", "))
+        })
+}
+
+#[cfg(not(feature = "builtin-wordlists"))]
+fn read_builtin_wordlist(name: &str) -> Result<String> {
+    Err(anyhow!("ripgen was built without the builtin-wordlists feature, so {}{} isn't available", BUILTIN_PREFIX, name))
+}
+
+/// Reads a wordlist file, decompressing it if it's gzip-compressed, or every file in a directory
+/// and its subdirectories in name order.
+fn read_wordlist_path(path: &Path) -> Result<String> {
+    if path.is_dir() {
+        let mut wordlists = Vec::new();
+        read_wordlist_dir(path, &mut HashSet::new(), &mut wordlists)?;
+
+        return Ok(wordlists.join("\n"));
+    }
+
+    Ok(String::from_utf8(read_wordlist_file(path)?)?)
+}
+
+/// Reads every wordlist in a directory and its subdirectories in name order. Directories that were
+/// already read, e.g. through a symlink loop, are skipped, as are entries that aren't regular files
+/// and files that aren't UTF-8 text.
+fn read_wordlist_dir(path: &Path, visited: &mut HashSet<PathBuf>, wordlists: &mut Vec<String>) -> Result<()> {
+    if !visited.insert(canonicalize(path)?) {
+        return Ok(());
+    }
+
+    let mut entries: Vec<PathBuf> = read_dir(path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<_>>()?;
+    entries.sort();
+
+    for entry in entries {
+        if entry.is_dir() {
+            read_wordlist_dir(&entry, visited, wordlists)?;
+        } else if entry.is_file() {
+            match String::from_utf8(read_wordlist_file(&entry)?) {
+                Ok(wordlist) => wordlists.push(wordlist),
+                Err(_) => eprintln!("Skipping {}, which isn't a UTF-8 wordlist", entry.display())
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads a wordlist file's bytes, decompressing them if the file is gzip-compressed.
+fn read_wordlist_file(path: &Path) -> Result<Vec<u8>> {
+    let bytes = read(path)?;
+
+    // gzip's magic number
+    if bytes.starts_with(&[0x1f, 0x8b]) {
+        let mut output = Vec::new();
+        MultiGzDecoder::new(bytes.as_slice()).read_to_end(&mut output)?;
+
+        return Ok(output);
+    }
+
+    Ok(bytes)
+}
@@ -1,71 +1,496 @@
 #![deny(warnings)]
 
 mod args;
+mod output;
 
-use std::io::{BufWriter, stdout, Write};
-use ripgen_lib::{RipGenIterator, RipGenManager};
-use crate::args::Args;
-use clap::Parser;
+use std::fs::read_to_string;
+#[cfg(feature = "resolve")]
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use ripgen_lib::{DomainComponents, RipGenIterator, RipGenManager, WordScope, WordSource, WordlistIterator};
+use ripgen_lib::markov::{Granularity, NgramModel, NgramOptions};
+use ripgen_lib::feedback::FeedbackModel;
+use ripgen_lib::rank::{BoundedRanking, Scorer, TransformPriors};
+use ripgen_lib::rounds::RoundDriver;
+use ripgen_lib::checkpoint::{Checkpoint, CheckpointTracker, Resumable};
+use ripgen_lib::wordlist::Wordlist;
+#[cfg(feature = "resolve")]
+use ripgen_lib::resolve::Resolver;
+use crate::args::{Args, Command, Profile};
+use crate::output::LineWriter;
 
 const FAST_MODE_WORDLIST_LEN: usize = 10;
 const DEFAULT_WORD_LEN: usize = 5;
 
 fn main() {
-    let args: Args = Args::parse();
+    let args = Args::parse_checked();
+
+    let feedback = match args.model {
+        Some(ref path) => FeedbackModel::load(path).expect("Failed to read in feedback model."),
+        None => FeedbackModel::default()
+    };
 
     let domains = args.get_domain_str()
         .expect("Failed to read in domains.");
     let wordlist = args.get_wordlist_str()
         .expect("Failed to read in wordlist file.");
+    let markov_corpus = args.get_markov_corpus_str()
+        .expect("Failed to read in markov corpus file.");
     let word_len = args.min_word_len.unwrap_or(DEFAULT_WORD_LEN);
 
-    let wordlist_lines = get_wordlist(&wordlist, &args);
+    let mut wordlist = get_wordlist(&wordlist, &args)
+        .expect("Failed to parse wordlist file.");
+    let uses_domain_words = args.profile.uses_domain_words();
+    let word_filter = |word: &&str| uses_domain_words && word.len() >= word_len;
+
+    if let Some(Command::Learn { ref confirmed }) = args.command {
+        let manager = RipGenManager::new(domains.lines(), wordlist.words(), &word_filter)
+            .expect("Failed to create ripgen iterator");
+
+        learn(&manager, feedback, confirmed, &args);
+        return;
+    }
+
+    // words are only pruned once learned from, otherwise they could never become productive
+    let word_filter = |word: &&str| word_filter(word) && (!args.prune_words || feedback.is_productive_word(word));
+    if args.prune_words {
+        wordlist.retain(|entry| feedback.is_productive_word(entry.word));
+    }
+
+    let mut driver = RoundDriver::new(domains.lines(), wordlist.words())
+        .with_word_weights(wordlist.entries().iter().map(|entry| (entry.word, entry.weight)));
+    if let Some(shard) = args.shard {
+        driver = driver.with_shard(shard);
+    }
+    if args.scope_words_by_root {
+        driver = driver.with_word_scope(WordScope::Root);
+    }
+    let mut confirmed_source = args.confirmed_source()
+        .expect("Failed to open the source of confirmed names.");
+    let mut sink = OutputSink::new(&args);
+
+    let (checkpoint, output_state) = match args.resume {
+        true => CheckpointFile::load(args.checkpoint.as_ref().expect("--resume requires --checkpoint."))
+            .expect("Failed to read in checkpoint.")
+            .unwrap_or_default(),
+        false => Default::default()
+    };
+    let checkpoints = CheckpointTracker::new(checkpoint);
+    sink.writer.resume(&output_state).expect("Failed to restore the output files.");
+
+    if let Some(ref path) = args.checkpoint {
+        sink.checkpoint_to(path.clone(), args.checkpoint_every, checkpoints.clone());
+    }
+
+    for round in 0 .. args.rounds.max(1) {
+        if round > 0 {
+            // names we resolved ourselves stand in for names confirmed elsewhere
+            let confirmed = match sink.take_resolved() {
+                Some(resolved) => resolved,
+                None => confirmed_source
+                    .as_mut()
+                    .expect("A source of confirmed names must be specified with --confirmed-from.")
+                    .read_round()
+                    .expect("Failed to read in confirmed names.")
+            };
+
+            driver.add_confirmed(confirmed.into_iter());
+        }
+
+        let completed = driver.run_round(&word_filter, |manager, is_new| {
+            let mut scorer = Scorer::new(manager);
+            scorer.apply_feedback(&feedback);
+            scorer.apply_weights(&wordlist);
+
+            // remembering every name is only worth the memory when there are later rounds
+            let words_for = transform_words(manager, &args, &wordlist);
+            let mut priors = TransformPriors::with_feedback(feedback.clone(), args.prune);
+
+            let rip_iter = generate(manager, &args, &mut priors, &checkpoints, &markov_corpus, &words_for)
+                .filter(|(name, _)| args.rounds <= 1 || is_new(name));
+
+            output(rip_iter, &args, &scorer, &priors, &mut sink)
+        }).expect("Failed to create ripgen iterator");
+
+        // stdout was closed so nobody is listening for further rounds
+        if !completed {
+            return;
+        }
+    }
+}
+
+/// Builds the chain of transforms selected by the profile and options.
+fn generate<'a>(
+    manager: &'a RipGenManager<'a>,
+    args: &'a Args,
+    priors: &mut TransformPriors,
+    checkpoints: &'a CheckpointTracker,
+    markov_corpus: &str,
+    words_for: &'a HashMap<String, WordSource<'a>>
+) -> Box<dyn Iterator<Item = (String, &'static str)> + 'a> {
+    // transforms without restricted words get the words of the chain
+    let words = |transform: &str| words_for.get(transform).map(WordSource::iter);
+
+    if args.profile == Profile::Infer {
+        let model = ripgen_lib::infer::PatternModel::learn(manager.domains());
+
+        // inferred names aren't built from a domain and a word, so they're sharded by position instead
+        let names = model.generate(args.infer_limit)
+            .enumerate()
+            .filter(|(position, _)| args.shard.is_none_or(|shard| shard.contains(*position)))
+            .map(|(_, name)| name)
+            .collect::<Vec<String>>();
+
+        // inferred names have no domain, so the checkpoint only records how many were output
+        let total = names.len();
+        let names = names
+            .into_iter()
+            .skip(checkpoints.current().offset)
+            .map(|name| (name, args.profile.name()));
+
+        return Box::new(checkpoints.track(names, move |names| Checkpoint {
+            offset: total - names.len(),
+            ..Checkpoint::default()
+        }));
+    }
+
+    if args.profile == Profile::Altdns {
+        let chain = manager
+            .transform_per_word(words("altdns-insert"), priors.with_prior("altdns-insert", 1.0, ripgen_lib::altdns::insert_word_transform))
+            .chain_transform_per_word(words("altdns-dash"), priors.with_prior("altdns-dash", 1.0, ripgen_lib::altdns::dash_word_transform))
+            .chain_transform(optional(args.add_number_suffix.then_some(priors.with_prior("altdns-number-suffix", 1.0, ripgen_lib::altdns::number_suffix_transform))))
+            .chain_transform(priors.with_prior("altdns-increase-number", 1.0, ripgen_lib::altdns::increase_number_transform))
+            .chain_transform(priors.with_prior("altdns-decrease-number", 1.0, ripgen_lib::altdns::decrease_number_transform))
+            .chain_transform_per_word(words("altdns-join"), priors.with_prior("altdns-join", 1.0, ripgen_lib::altdns::join_word_transform));
+        let mut rip_iter = priors.attribute(chain);
+
+        rip_iter.resume(checkpoints.current());
+
+        // like altdns, every name is written once and the input names are left out
+        let mut seen: HashSet<String> = manager.domains().map(|domain| domain.all().join(".")).collect();
+
+        return Box::new(checkpoints.track(rip_iter, Resumable::checkpoint).filter(move |(name, _)| seen.insert(name.clone())));
+    }
+
+    let mut swap_roots = args.swap_roots.clone();
+    if args.swap_all_roots {
+        swap_roots.extend(manager.roots().into_iter().map(str::to_string));
+    }
+
+    let use_depth = args.uses_depth_permutation();
+
+    // the model is trained on every domain up front, so it's only built when asked for
+    let markov_transform = args.markov.then(|| {
+        let mut markov_model = NgramModel::new(NgramOptions {
+            order: args.markov_order,
+            granularity: if args.markov_tokens { Granularity::Token } else { Granularity::Char },
+            min_probability: args.markov_min_probability,
+            max_labels: args.markov_limit,
+            seed: args.markov_seed,
+            ..NgramOptions::default()
+        });
+
+        markov_model.train_domains(manager.domains());
+        markov_model.train_corpus(markov_corpus.lines());
+        markov_model.transform(manager.domains())
+    });
+
+    // rough likelihoods of each transform producing a real name, used when ranking output
+    let chain = manager
+        .transform_with_words(words("substring-swap"), optional(args.substring_swap.then_some(priors.with_prior("substring-swap", 0.5, ripgen_lib::dnsgen::swap_word_transform))))
+        .chain_transform_with_words(words("token-swap"), optional((!args.substring_swap).then_some(priors.with_prior("token-swap", 0.6, ripgen_lib::variants::token_swap_transform))))
+        .chain_transform_per_word(words("permute"), optional((!use_depth).then_some(priors.with_prior("permute", 0.8, ripgen_lib::dnsgen::permute_words_transform))))
+        .chain_transform_with_words(words("depth-permute"), optional(use_depth.then_some(priors.with_prior("depth-permute", 0.6, ripgen_lib::variants::depth_permute_transform(args.depth, args.depth_numbers, args.depth_cap())))))
+        .chain_transform(priors.with_prior("numbers", 0.9, ripgen_lib::dnsgen::numbers_transform))
+        .chain_transform_per_word(words("dash"), priors.with_prior("dash", 0.5, ripgen_lib::dnsgen::dash_transform))
+        .chain_transform(priors.with_prior("root-swap", 0.4, ripgen_lib::variants::root_swap_transform(swap_roots)))
+        .chain_transform(priors.with_prior("suffix-swap", 0.3, ripgen_lib::variants::suffix_swap_transform(args.swap_suffixes.clone())))
+        .chain_transform(optional(args.delete_labels.then_some(priors.with_prior("label-deletion", 0.5, ripgen_lib::variants::label_deletion_transform))))
+        .chain_transform(optional(args.reorder_labels.then_some(priors.with_prior("label-reorder", 0.3, ripgen_lib::variants::label_reorder_transform))))
+        .chain_transform(optional(args.vary_separators.then_some(priors.with_prior("separators", 0.4, ripgen_lib::variants::separator_transform))))
+        .chain_transform(optional(args.typo.then_some(priors.with_prior("typo", 0.2, ripgen_lib::typo::typo_transform))))
+        .chain_transform(priors.with_prior("markov", 0.3, optional(markov_transform)));
+    let mut rip_iter = priors.attribute(chain);
+
+    rip_iter.resume(checkpoints.current());
+
+    Box::new(checkpoints.track(rip_iter, Resumable::checkpoint))
+}
+
+/// Returns the words of each transform restricted by `--words-for`, out of the manager's words.
+fn transform_words<'a>(manager: &'a RipGenManager<'a>, args: &Args, wordlist: &Wordlist) -> HashMap<String, WordSource<'a>> {
+    args.words_for
+        .iter()
+        .map(|restriction| {
+            let wordlist_words: Option<HashSet<&str>> = restriction.needs_wordlist().then(|| {
+                wordlist
+                    .entries()
+                    .iter()
+                    .filter(|entry| restriction.accepts_entry(entry))
+                    .map(|entry| entry.word)
+                    .collect()
+            });
+
+            let source = manager.word_source(|word| {
+                restriction.accepts_len(word) && wordlist_words.as_ref().is_none_or(|words| words.contains(word))
+            });
+
+            (restriction.transform.clone(), source)
+        })
+        .collect()
+}
+
+/// Wraps `transform` so it only produces output when there is one. This lets opt-in transforms
+/// sit in the chain without changing its type.
+fn optional<'a, F, O>(
+    transform: Option<F>
+) -> impl Fn(&'a DomainComponents<'a>, WordlistIterator<'a>) -> std::iter::Flatten<std::option::IntoIter<O>>
+    where
+        F: Fn(&'a DomainComponents<'a>, WordlistIterator<'a>) -> O,
+        O: Iterator<Item = String>
+{
+    move |domain_components, words| {
+        transform
+            .as_ref()
+            .map(|transform| transform(domain_components, words))
+            .into_iter()
+            .flatten()
+    }
+}
+
+/// Streams the output, or only the best scoring names from best to worst if ranking was requested.
+///
+/// Returns false if stdout was closed before all of the output was written.
+fn output(
+    rip_iter: impl Iterator<Item = (String, &'static str)>,
+    args: &Args,
+    scorer: &Scorer,
+    priors: &TransformPriors,
+    sink: &mut OutputSink
+) -> bool {
+    let line = |name: String, transform: &str| match args.provenance {
+        true => format!("{}\t{}", name, transform),
+        false => name
+    };
+
+    let capacity = match args.ranked {
+        Some(capacity) => capacity,
+        None => return sink.write(rip_iter.map(|(name, transform)| line(name, transform)))
+    };
+
+    // a name produced by several transforms is only output once, attributed to its best one
+    let mut ranking = BoundedRanking::new(capacity);
+
+    for (name, transform) in rip_iter {
+        let score = scorer.score(&name) + priors.score(transform);
+        ranking.push_with(name, transform, score);
+    }
+
+    sink.write(ranking.into_sorted_entries().into_iter().map(|(name, transform)| line(name, transform)))
+}
+
+/// Writes each round's output, or only the names that resolve when using `ripgen resolve`.
+struct OutputSink {
+    writer: LineWriter,
+    checkpoints: Option<CheckpointFile>,
+    #[cfg(feature = "resolve")]
+    resolver: Option<Resolver>,
+    /// The names that resolved in the last round.
+    #[cfg(feature = "resolve")]
+    resolved: Vec<String>
+}
+
+impl OutputSink {
+    fn new(args: &Args) -> Self {
+        Self {
+            writer: LineWriter::new(args).expect("Failed to open the output files."),
+            checkpoints: None,
+            #[cfg(feature = "resolve")]
+            resolver: args.resolver().expect("Failed to configure the resolver."),
+            #[cfg(feature = "resolve")]
+            resolved: Vec::new()
+        }
+    }
+
+    /// Saves a checkpoint to `path` every `every` lines written, and once all of them were written.
+    fn checkpoint_to(&mut self, path: String, every: usize, tracker: CheckpointTracker) {
+        self.checkpoints = Some(CheckpointFile { path, every: every.max(1), tracker });
+    }
+
+    /// Returns false if stdout was closed before all of the output was written.
+    fn write(&mut self, lines: impl Iterator<Item = String>) -> bool {
+        #[cfg(feature = "resolve")]
+        if let Some(ref resolver) = self.resolver {
+            return resolve_output(resolver, lines, &mut self.writer, &mut self.resolved);
+        }
+
+        stream_output(lines, &mut self.writer, self.checkpoints.as_ref())
+    }
 
-    let manager = RipGenManager::new(
-        domains.lines(),
-        wordlist_lines,
-        &|word| word.len() >= word_len
-    ).expect("Failed to create ripgen iterator");
+    /// Returns the names that resolved in the last round, or `None` if this sink isn't resolving.
+    fn take_resolved(&mut self) -> Option<Vec<String>> {
+        #[cfg(feature = "resolve")]
+        if self.resolver.is_some() {
+            return Some(std::mem::take(&mut self.resolved));
+        }
+
+        None
+    }
+}
+
+/// Resolves the name at the start of each line and writes out the lines of the names that resolved
+/// to something other than a wildcard, in the order they resolved.
+#[cfg(feature = "resolve")]
+fn resolve_output(
+    resolver: &Resolver,
+    lines: impl Iterator<Item = String>,
+    writer: &mut LineWriter,
+    resolved: &mut Vec<String>
+) -> bool {
+    let mut open = true;
+
+    // lines may carry provenance, so only the name is resolved and its line is held until it resolves.
+    // the names are pulled and resolved on this thread, never at the same time
+    let pending = RefCell::new(HashMap::new());
+    let names = lines.map(|line| {
+        let name = line.split('\t').next().unwrap_or_default().to_string();
+        pending.borrow_mut().insert(name.clone(), line);
+        name
+    });
+
+    let result = resolver.resolve_blocking(names, |resolution| {
+        let line = pending.borrow_mut().remove(&resolution.name);
 
-    let rip_iter = manager
-        .transform(ripgen_lib::dnsgen::swap_word_transform)
-        .chain_transform(ripgen_lib::dnsgen::permute_words_transform)
-        .chain_transform(ripgen_lib::dnsgen::numbers_transform)
-        .chain_transform(ripgen_lib::dnsgen::dash_transform);
+        if !resolution.is_genuine() {
+            return true;
+        }
+
+        if !is_open(writer.write_line(&line.unwrap_or_else(|| resolution.name.clone()))) {
+            open = false;
+            return false;
+        }
+
+        resolved.push(resolution.name);
+        true
+    });
 
-    stream_output(rip_iter);
+    result.expect("Failed to resolve names.");
+
+    is_open(writer.flush()) && open
 }
 
-fn stream_output(rip_iter: impl Iterator<Item = String>) {
-    let out = stdout();
-    let stdout_lock = out.lock();
-    let mut buf = BufWriter::new(stdout_lock);
+/// Records which of the generated names were confirmed to resolve in the feedback model.
+fn learn(manager: &RipGenManager, mut feedback: FeedbackModel, confirmed_path: &str, args: &Args) {
+    let model_path = args.model
+        .as_ref()
+        .expect("A feedback model file must be specified with --model.");
+    let confirmed = read_to_string(confirmed_path)
+        .expect("Failed to read in confirmed names.");
+
+    // lines are either `name` or `name<TAB>transform`, as output with --provenance
+    let confirmed_names = confirmed
+        .lines()
+        .map(|line| {
+            let mut fields = line.split('\t');
+            let name = fields.next().unwrap_or_default();
+
+            (name, fields.next().map(str::trim).filter(|transform| !transform.is_empty()))
+        });
 
-    for line in rip_iter {
-        if writeln!(buf, "{}", line).is_err() {
+    feedback.learn(manager, confirmed_names);
+    feedback.save(model_path).expect("Failed to write feedback model.");
+}
+
+fn stream_output(rip_iter: impl Iterator<Item = String>, writer: &mut LineWriter, checkpoints: Option<&CheckpointFile>) -> bool {
+    for (written, line) in rip_iter.enumerate() {
+        if !is_open(writer.write_line(&line)) {
             // user might be using `head` to only grab the first couple of entries - we should exit
-            let _ = buf.flush();
-            return;
+            let _ = writer.flush();
+            return false;
         }
+
+        if let Some(checkpoints) = checkpoints.filter(|checkpoints| (written + 1) % checkpoints.every == 0) {
+            if !is_open(writer.flush()) {
+                return false;
+            }
+
+            checkpoints.save(writer);
+        }
+    }
+
+    // flushed so whoever is resolving this round sees all of it before we wait on confirmed names
+    if !is_open(writer.flush()) {
+        return false;
+    }
+
+    if let Some(checkpoints) = checkpoints {
+        checkpoints.save(writer);
     }
 
-    let _ = buf.flush();
+    true
+}
+
+/// Returns false if the output was closed by whoever is reading it. Any other failure to write, such
+/// as running out of disk space or file handles, is fatal rather than silently cutting the output short.
+fn is_open(result: std::io::Result<()>) -> bool {
+    match result {
+        Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => false,
+        result => {
+            result.expect("Failed to write output.");
+            true
+        }
+    }
 }
 
-fn get_wordlist<'a>(wordlist: &'a str, args: &Args) -> impl Iterator<Item = &'a str> {
+/// Where and how often checkpoints are saved.
+struct CheckpointFile {
+    path: String,
+    every: usize,
+    tracker: CheckpointTracker
+}
+
+impl CheckpointFile {
+    /// Reads a checkpoint and the state of the output files saved with it, if there is one.
+    fn load(path: &str) -> anyhow::Result<Option<(Checkpoint, String)>> {
+        let contents = match read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into())
+        };
+
+        let (checkpoint, output_state) = contents.split_once('\n').unwrap_or((&contents, ""));
+
+        Ok(Some((checkpoint.parse()?, output_state.to_string())))
+    }
+
+    /// Saves the position of the last name that was written, followed by the state of the output
+    /// files so that a resumed run can cut off anything written after it. Must only be called once
+    /// the output is flushed.
+    fn save(&self, writer: &LineWriter) {
+        let partial = format!("{}.partial", self.path);
+
+        // replaced in one step so that being killed midway leaves the previous checkpoint in place
+        std::fs::write(&partial, format!("{}\n{}", self.tracker.current(), writer.state()))
+            .and_then(|_| std::fs::rename(&partial, &self.path))
+            .expect("Failed to write checkpoint.");
+    }
+}
+
+fn get_wordlist<'a>(wordlist: &'a str, args: &Args) -> anyhow::Result<Wordlist<'a>> {
+    let mut wordlist = Wordlist::parse(wordlist)?;
+    let tags = args.word_tags()?;
+
+    wordlist.retain(|entry| {
+        (tags.is_empty() || tags.iter().any(|(key, value)| entry.has_tag(key, value)))
+            && args.min_word_weight.is_none_or(|min_weight| entry.weight >= min_weight)
+    });
+
     // https://github.com/ProjectAnte/dnsgen/blob/16daeef81205e7663708b3ee11d759215c7168fe/dnsgen/dnsgen.py#L220
-    let mut wordlist_iter = None;
-    let mut fast_wordlist_iter = None;
+    if let Some(true) = args.fast {
+        let fast_words: HashSet<&str> = wordlist.top(FAST_MODE_WORDLIST_LEN).into_iter().collect();
 
-    match args.fast {
-        Some(fast_mode) if fast_mode => {
-            fast_wordlist_iter = Some(wordlist.lines().take(FAST_MODE_WORDLIST_LEN))
-        },
-        _ => wordlist_iter = Some(wordlist.lines())
+        wordlist.retain(|entry| fast_words.contains(entry.word));
     }
 
-    wordlist_iter
-        .into_iter()
-        .flatten()
-        .chain(fast_wordlist_iter.into_iter().flatten())
-}
\ No newline at end of file
+    Ok(wordlist)
+}
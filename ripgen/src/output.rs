@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fs::{create_dir_all, File, OpenOptions};
+use std::io::{BufWriter, Error, ErrorKind, Result, stdout, Stdout, Write};
+use std::path::PathBuf;
+use ripgen_lib::DomainComponents;
+use crate::args::{Args, OutputFormat};
+
+/// The most output files kept open at once, well below common limits on open files. Files that are
+/// written to again after being closed are reopened for appending.
+const MAX_OPEN_FILES: usize = 128;
+
+/// Writes output lines to stdout, or to files grouped by root domain and split into shards.
+pub struct LineWriter {
+    format: OutputFormat,
+    trailing_dot: bool,
+    target: Target
+}
+
+enum Target {
+    Stdout(BufWriter<Stdout>),
+    Files(OutputFiles)
+}
+
+struct OutputFiles {
+    dir: PathBuf,
+    by_root: bool,
+    shards: usize,
+    /// The open files, along with the number of lines written when each was last written to.
+    files: HashMap<String, (BufWriter<File>, u64)>,
+    /// The number of bytes written to every file created so far. These are appended to rather than
+    /// truncated when reopened.
+    sizes: HashMap<String, u64>,
+    lines: u64,
+    /// The number of names written for each root, or for all of them if not split by root.
+    written: HashMap<String, usize>
+}
+
+impl LineWriter {
+    pub(crate) fn new(args: &Args) -> Result<Self> {
+        let target = match args.output_dir {
+            Some(ref dir) => {
+                create_dir_all(dir)?;
+
+                Target::Files(OutputFiles {
+                    dir: PathBuf::from(dir),
+                    by_root: args.split_by_root,
+                    shards: args.output_shards.unwrap_or(1).max(1),
+                    files: HashMap::new(),
+                    sizes: HashMap::new(),
+                    lines: 0,
+                    written: HashMap::new()
+                })
+            },
+            None => Target::Stdout(BufWriter::new(stdout()))
+        };
+
+        Ok(Self {
+            format: args.format,
+            trailing_dot: args.trailing_dot,
+            target
+        })
+    }
+
+    /// Writes a line, which is a name optionally followed by a tab and more fields.
+    pub(crate) fn write_line(&mut self, line: &str) -> Result<()> {
+        let (name, rest) = match line.split_once('\t') {
+            Some((name, rest)) => (name, Some(rest)),
+            None => (line, None)
+        };
+
+        let mut output = name.to_string();
+
+        if self.trailing_dot || self.format == OutputFormat::Massdns {
+            output.push('.');
+        }
+
+        if self.format == OutputFormat::Massdns {
+            output.push_str(" A");
+        }
+
+        if let Some(rest) = rest {
+            output.push('\t');
+            output.push_str(rest);
+        }
+
+        output.push('\n');
+
+        match self.target {
+            Target::Stdout(ref mut out) => out.write_all(output.as_bytes()),
+            Target::Files(ref mut files) => files.write(name, &output)
+        }
+    }
+
+    /// Describes how much was written to each output file, to save along with a checkpoint once
+    /// the output is flushed. Writing to stdout has nothing to describe.
+    pub(crate) fn state(&self) -> String {
+        let files = match self.target {
+            Target::Stdout(_) => return String::new(),
+            Target::Files(ref files) => files
+        };
+
+        let sizes = files.sizes
+            .iter()
+            .map(|(file_name, size)| format!("file {} {}\n", size, file_name));
+        let written = files.written
+            .iter()
+            .map(|(root, written)| format!("root {} {}\n", written, root));
+
+        sizes.chain(written).collect()
+    }
+
+    /// Continues the output described by [state](Self::state). Anything written to the files after
+    /// the state was saved is cut off, since it's written again when generation resumes.
+    pub(crate) fn resume(&mut self, state: &str) -> Result<()> {
+        let files = match self.target {
+            Target::Stdout(_) => return Ok(()),
+            Target::Files(ref mut files) => files
+        };
+
+        for line in state.lines().filter(|line| !line.is_empty()) {
+            let invalid = || Error::new(ErrorKind::InvalidData, format!("Invalid output state: {}", line));
+
+            let (kind, line) = line.split_once(' ').ok_or_else(invalid)?;
+            let (count, name) = line.split_once(' ').ok_or_else(invalid)?;
+
+            match kind {
+                "file" => {
+                    let size = count.parse().map_err(|_| invalid())?;
+                    OpenOptions::new().write(true).open(files.dir.join(name))?.set_len(size)?;
+                    files.sizes.insert(name.to_string(), size);
+                },
+                "root" => {
+                    files.written.insert(name.to_string(), count.parse().map_err(|_| invalid())?);
+                },
+                _ => return Err(invalid())
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn flush(&mut self) -> Result<()> {
+        match self.target {
+            Target::Stdout(ref mut out) => out.flush(),
+            Target::Files(ref mut files) => files.files
+                .values_mut()
+                .try_for_each(|(file, _)| file.flush())
+        }
+    }
+}
+
+impl OutputFiles {
+    /// Writes a name's output to its file. The shards of each root are filled in turn so they stay
+    /// about the same size.
+    fn write(&mut self, name: &str, output: &str) -> Result<()> {
+        let root = match self.by_root {
+            true => DomainComponents::try_from(name)
+                .map(|domain| domain.root().to_string())
+                .unwrap_or_else(|_| "invalid".to_string()),
+            false => String::new()
+        };
+
+        let written = self.written.entry(root.clone()).or_default();
+        let shard = *written % self.shards;
+        *written += 1;
+
+        let file_name = match (self.by_root, self.shards > 1) {
+            (true, true) => format!("{}.{}.txt", root, shard),
+            (true, false) => format!("{}.txt", root),
+            (false, _) => format!("shard-{}.txt", shard)
+        };
+
+        if !self.files.contains_key(&file_name) {
+            if self.files.len() >= MAX_OPEN_FILES {
+                self.close_least_recent()?;
+            }
+
+            let path = self.dir.join(&file_name);
+            let file = match self.sizes.contains_key(&file_name) {
+                true => OpenOptions::new().append(true).open(path)?,
+                false => File::create(path)?
+            };
+
+            self.files.insert(file_name.clone(), (BufWriter::new(file), 0));
+        }
+
+        self.lines += 1;
+
+        let (file, last_written) = self.files.get_mut(&file_name).expect("Output file was just opened.");
+        *last_written = self.lines;
+
+        file.write_all(output.as_bytes())?;
+        *self.sizes.entry(file_name).or_default() += output.len() as u64;
+
+        Ok(())
+    }
+
+    /// Flushes and closes the file that was written to the longest time ago.
+    fn close_least_recent(&mut self) -> Result<()> {
+        let least_recent = self.files
+            .iter()
+            .min_by_key(|(_, (_, last_written))| *last_written)
+            .map(|(file_name, _)| file_name.clone());
+
+        if let Some((mut file, _)) = least_recent.and_then(|file_name| self.files.remove(&file_name)) {
+            file.flush()?;
+        }
+
+        Ok(())
+    }
+}
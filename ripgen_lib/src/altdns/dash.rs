@@ -0,0 +1,35 @@
+use crate::{DomainComponents, WordlistIterator};
+
+/// Joins every word to every subdomain label with a dash, both as `label-word` and `word-label`
+/// (altdns's `insert_dash_subdomains`).
+pub fn dash_word_transform<'domain>(
+    domain_components: &'domain DomainComponents,
+    words: WordlistIterator<'domain>,
+) -> impl Iterator<Item = String> + 'domain {
+    let subdomains = domain_components.subdomains();
+
+    words
+        .flat_map(move |word| {
+            (0 .. subdomains.len())
+                .flat_map(move |idx| {
+                    [
+                        format!("{}-{}", subdomains[idx], word),
+                        format!("{}-{}", word, subdomains[idx])
+                    ]
+                        .into_iter()
+                        .map(move |new_label| replace_label(domain_components, idx, &new_label))
+                })
+        })
+}
+
+pub(super) fn replace_label(domain_components: &DomainComponents, idx: usize, new_label: &str) -> String {
+    let all = domain_components.all();
+
+    let new_sub: Vec<&str> = [
+        &all[.. idx],
+        [new_label].as_slice(),
+        &all[idx + 1 ..]
+    ].concat();
+
+    new_sub.join(".")
+}
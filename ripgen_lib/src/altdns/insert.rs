@@ -0,0 +1,43 @@
+use crate::{DomainComponents, WordlistIterator};
+
+/// Inserts every word at every position of the subdomain, including after the last subdomain label
+/// (altdns's `insert_all_indexes`).
+///
+/// ```
+/// # use ripgen_lib::RipGenManager;
+/// # use ripgen_lib::altdns::insert_word_transform;
+/// let domains = vec!["a.b.example.com"];
+/// let words = vec!["dev"];
+/// let manager = RipGenManager::new(domains.into_iter(), words.into_iter(), &|_| false)
+///     .expect("Failed to parse domains.");
+///
+/// let mut output: Vec<String> = manager
+///     .transform(insert_word_transform)
+///     .collect();
+/// output.sort();
+///
+/// assert_eq!(output, vec!["a.b.dev.example.com", "a.dev.b.example.com", "dev.a.b.example.com"]);
+/// ```
+pub fn insert_word_transform<'domain>(
+    domain_components: &'domain DomainComponents,
+    words: WordlistIterator<'domain>,
+) -> impl Iterator<Item = String> + 'domain {
+    let subdomains = domain_components.subdomains();
+
+    words
+        .flat_map(move |word| {
+            // altdns has no subdomain to insert into for an apex domain
+            (0 ..= subdomains.len())
+                .filter(move |_| !subdomains.is_empty())
+                .map(move |idx| {
+                    let new_sub: Vec<&str> = [
+                        &subdomains[.. idx],
+                        [*word].as_slice(),
+                        &subdomains[idx ..],
+                        [domain_components.root()].as_slice()
+                    ].concat();
+
+                    new_sub.join(".")
+                })
+        })
+}
@@ -0,0 +1,39 @@
+use crate::{DomainComponents, WordlistIterator};
+use super::dash::replace_label;
+
+/// Joins every word to every subdomain label without a separator, both as `labelword` and
+/// `wordlabel` (altdns's `join_words_subdomains`).
+///
+/// ```
+/// # use ripgen_lib::RipGenManager;
+/// # use ripgen_lib::altdns::join_word_transform;
+/// let domains = vec!["a.b.example.com"];
+/// let words = vec!["dev"];
+/// let manager = RipGenManager::new(domains.into_iter(), words.into_iter(), &|_| false)
+///     .expect("Failed to parse domains.");
+///
+/// let output: Vec<String> = manager
+///     .transform(join_word_transform)
+///     .collect();
+///
+/// assert_eq!(output, vec!["adev.b.example.com", "deva.b.example.com", "a.bdev.example.com", "a.devb.example.com"]);
+/// ```
+pub fn join_word_transform<'domain>(
+    domain_components: &'domain DomainComponents,
+    words: WordlistIterator<'domain>,
+) -> impl Iterator<Item = String> + 'domain {
+    let subdomains = domain_components.subdomains();
+
+    words
+        .flat_map(move |word| {
+            (0 .. subdomains.len())
+                .flat_map(move |idx| {
+                    [
+                        format!("{}{}", subdomains[idx], word),
+                        format!("{}{}", word, subdomains[idx])
+                    ]
+                        .into_iter()
+                        .map(move |new_label| replace_label(domain_components, idx, &new_label))
+                })
+        })
+}
@@ -0,0 +1,14 @@
+//! Transforms that reproduce the output of [altdns](https://github.com/infosec-au/altdns).
+//!
+//! altdns only permutes with its wordlist, so managers used with these transforms will typically
+//! reject every word extracted from the input domains.
+
+mod insert;
+mod numbers;
+mod dash;
+mod join;
+
+pub use insert::insert_word_transform;
+pub use numbers::{number_suffix_transform, increase_number_transform, decrease_number_transform};
+pub use dash::dash_word_transform;
+pub use join::join_word_transform;
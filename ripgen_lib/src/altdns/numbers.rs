@@ -0,0 +1,82 @@
+use regex::Regex;
+use lazy_static::lazy_static;
+use crate::{DomainComponents, WordlistIterator};
+
+lazy_static! {
+    static ref DIGIT_REGEX: Regex = Regex::new(r"\d{1,3}").unwrap();
+}
+
+const NUMBER_OFFSETS: u64 = 3;
+
+/// Appends the digits 0-9 to every subdomain label, both directly and after a dash
+/// (altdns's `insert_number_suffix_subdomains`).
+///
+/// Like altdns, apex domains are treated as having a single empty subdomain label.
+pub fn number_suffix_transform(
+    domain_components: &DomainComponents,
+    _: WordlistIterator
+) -> std::vec::IntoIter<String> {
+    let subdomain_string: String = domain_components.subdomains().join(".");
+    let labels: Vec<&str> = subdomain_string.split('.').collect();
+    let root = domain_components.root();
+
+    let mut results = Vec::with_capacity(labels.len() * 20);
+
+    for idx in 0 .. labels.len() {
+        for num in 0 .. 10 {
+            for new_label in [format!("{}-{}", labels[idx], num), format!("{}{}", labels[idx], num)] {
+                let new_sub: Vec<&str> = [
+                    &labels[.. idx],
+                    [new_label.as_str()].as_slice(),
+                    &labels[idx + 1 ..],
+                    [root].as_slice()
+                ].concat();
+
+                results.push(new_sub.join("."));
+            }
+        }
+    }
+
+    results.into_iter()
+}
+
+/// Increases every number of up to three digits in the name by 1 through 3 (altdns's
+/// `increase_num_found`).
+///
+/// Unlike dnsgen, altdns also looks for numbers in the root.
+pub fn increase_number_transform(
+    domain_components: &DomainComponents,
+    _: WordlistIterator
+) -> std::vec::IntoIter<String> {
+    replace_numbers(domain_components, |num, offset| num.checked_add(offset))
+}
+
+/// Decreases every number of up to three digits in the name by 1 through 3, stopping at zero
+/// (altdns's `decrease_num_found`).
+pub fn decrease_number_transform(
+    domain_components: &DomainComponents,
+    _: WordlistIterator
+) -> std::vec::IntoIter<String> {
+    replace_numbers(domain_components, |num, offset| num.checked_sub(offset))
+}
+
+fn replace_numbers(
+    domain_components: &DomainComponents,
+    apply_offset: impl Fn(u64, u64) -> Option<u64>
+) -> std::vec::IntoIter<String> {
+    let domain_str: String = domain_components.all().join(".");
+
+    DIGIT_REGEX
+        .find_iter(&domain_str)
+        .flat_map(|found| {
+            let detected_int_str = found.as_str();
+            let detected_int: u64 = detected_int_str.parse().unwrap();
+
+            (1 ..= NUMBER_OFFSETS)
+                .map_while(|offset| apply_offset(detected_int, offset))
+                .map(|new_int| domain_str.replace(detected_int_str, &new_int.to_string()))
+                .collect::<Vec<String>>()
+        })
+        .collect::<Vec<String>>()
+        .into_iter()
+}
@@ -0,0 +1,332 @@
+use std::borrow::Cow;
+use fxhash::{FxHashMap, FxHashSet};
+use crate::{DomainComponents, RipGenError, RipGenManager};
+use crate::shard::Shard;
+use crate::words::{Tokenizer, WordScope};
+
+type WordFilter<'domain> = Box<dyn Fn(&&str) -> bool + 'domain>;
+
+/// Configures and creates a [RipGenManager].
+///
+/// ```
+/// # use ripgen_lib::{RipGenManager, Tokenizer};
+/// let manager = RipGenManager::builder()
+///     .domains(vec!["api-v2.example.com", "api-v2.example.com", "www.other.com"].into_iter())
+///     .words(vec!["dev"].into_iter())
+///     .word_filter(|word| word.len() > 1)
+///     .tokenizer(Tokenizer::Segments)
+///     .scope(vec!["example.com"].into_iter())
+///     .dedup(true)
+///     .build()
+///     .expect("Failed to parse domains.");
+///
+/// let mut words: Vec<&str> = manager.words().collect();
+/// words.sort();
+///
+/// assert_eq!(manager.domains().count(), 1);
+/// assert_eq!(words, vec!["api", "api-v2", "dev"]);
+/// ```
+#[derive(Default)]
+pub struct RipGenManagerBuilder<'domain> {
+    /// The input domains along with the input group they were given in.
+    domains: Vec<(&'domain str, usize)>,
+    groups: usize,
+    words: Vec<&'domain str>,
+    word_weights: FxHashMap<&'domain str, f64>,
+    word_filters: Vec<WordFilter<'domain>>,
+    tokenizer: Tokenizer,
+    word_scope: WordScope,
+    suffixes: Vec<String>,
+    scope: Vec<String>,
+    dedup: bool,
+    normalize: bool,
+    shard: Option<Shard>
+}
+
+impl<'domain> RipGenManagerBuilder<'domain> {
+    /// Adds input domains. Empty lines are skipped.
+    pub fn domains(mut self, domains: impl Iterator<Item = &'domain str>) -> Self {
+        self.domains.extend(domains.map(|domain| (domain, 0)));
+        self
+    }
+
+    /// Adds input domains as a group of their own. With [WordScope::Group], they're only permuted
+    /// with each other's words. Domains added with [domains](Self::domains) form one group.
+    ///
+    /// ```
+    /// # use ripgen_lib::{RipGenIterator, RipGenManager, WordScope};
+    /// # use ripgen_lib::variants::depth_permute_transform;
+    /// let manager = RipGenManager::builder()
+    ///     .domain_group(vec!["api.corp-a.com", "www.corp-a.com"].into_iter())
+    ///     .domain_group(vec!["shop.corp-b.com"].into_iter())
+    ///     .word_scope(WordScope::Group)
+    ///     .build()
+    ///     .expect("Failed to parse domains.");
+    ///
+    /// let mut names: Vec<String> = manager.transform(depth_permute_transform(1, 0, None)).collect();
+    /// names.sort();
+    /// names.dedup();
+    ///
+    /// assert_eq!(names, vec!["api.api.corp-a.com", "api.www.corp-a.com", "shop.shop.corp-b.com", "www.api.corp-a.com", "www.www.corp-a.com"]);
+    /// ```
+    pub fn domain_group(mut self, domains: impl Iterator<Item = &'domain str>) -> Self {
+        self.groups += 1;
+
+        let group = self.groups;
+        self.domains.extend(domains.map(|domain| (domain, group)));
+        self
+    }
+
+    /// Adds wordlist entries. These are used as they are, without any word filter.
+    pub fn words(mut self, words: impl Iterator<Item = &'domain str>) -> Self {
+        self.words.extend(words);
+        self
+    }
+
+    /// Adds a filter on the words extracted from the input domains. A word is only used if it
+    /// passes every filter.
+    pub fn word_filter(mut self, filter: impl Fn(&&str) -> bool + 'domain) -> Self {
+        self.word_filters.push(Box::new(filter));
+        self
+    }
+
+    /// Sets the weight of words, e.g. the [weights](crate::wordlist::WordEntry::weight) of a
+    /// wordlist's entries. Transforms try the words from the highest to the lowest weight. Words
+    /// without a weight have a weight of 1, words with the same weight keep the order they were
+    /// first seen in and words given more than once use their highest weight.
+    ///
+    /// ```
+    /// # use ripgen_lib::{RipGenIterator, RipGenManager};
+    /// # use ripgen_lib::variants::depth_permute_transform;
+    /// # use ripgen_lib::wordlist::Wordlist;
+    /// let wordlist = Wordlist::parse("legacy 0.2\nadmin 3\nstaging").expect("Failed to parse wordlist.");
+    ///
+    /// let manager = RipGenManager::builder()
+    ///     .domains(vec!["example.com"].into_iter())
+    ///     .words(wordlist.words())
+    ///     .word_weights(wordlist.entries().iter().map(|entry| (entry.word, entry.weight)))
+    ///     .build()
+    ///     .expect("Failed to parse domains.");
+    ///
+    /// let names: Vec<String> = manager.transform(depth_permute_transform(1, 0, None)).collect();
+    ///
+    /// assert_eq!(names, vec!["admin.example.com", "staging.example.com", "legacy.example.com"]);
+    /// ```
+    pub fn word_weights(mut self, weights: impl Iterator<Item = (&'domain str, f64)>) -> Self {
+        for (word, weight) in weights {
+            let known = self.word_weights.entry(word).or_insert(weight);
+            *known = known.max(weight);
+        }
+
+        self
+    }
+
+    /// Sets how words are extracted from the input domains. Defaults to [Tokenizer::Dashes].
+    pub fn tokenizer(mut self, tokenizer: Tokenizer) -> Self {
+        self.tokenizer = tokenizer;
+        self
+    }
+
+    /// Sets which words each domain is permuted with. Defaults to [WordScope::Global]. With
+    /// [WordScope::Root] or [WordScope::Group], each domain is only permuted with the words
+    /// extracted from its own root or group plus the wordlist, so that one target's naming doesn't
+    /// leak into another's.
+    pub fn word_scope(mut self, word_scope: WordScope) -> Self {
+        self.word_scope = word_scope;
+        self
+    }
+
+    /// Adds suffixes that are treated as public suffixes on top of the public suffix list, such as
+    /// internal zones. See [DomainComponents::with_suffixes].
+    pub fn suffixes<S: Into<String>>(mut self, suffixes: impl Iterator<Item = S>) -> Self {
+        self.suffixes.extend(suffixes.map(|suffix| normalized(&suffix.into()).trim_start_matches('.').to_string()));
+        self
+    }
+
+    /// Restricts the input domains to those equal to or under one of `scope`. Everything is in
+    /// scope if no scope was given.
+    pub fn scope<S: Into<String>>(mut self, scope: impl Iterator<Item = S>) -> Self {
+        self.scope.extend(scope.map(|name| normalized(&name.into()).into_owned()));
+        self
+    }
+
+    /// Sets whether repeated input domains are only used once. Defaults to false. Domains that only
+    /// differ in case or a trailing dot are the same domain.
+    pub fn dedup(mut self, dedup: bool) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    /// Sets whether surrounding whitespace and a trailing dot are trimmed from the input domains
+    /// before they're parsed. Defaults to false. Domains are borrowed rather than copied, so they
+    /// keep their case; scope, suffixes and deduplication ignore case either way.
+    ///
+    /// ```
+    /// # use ripgen_lib::RipGenManager;
+    /// let manager = RipGenManager::builder()
+    ///     .domains(vec![" www.example.com.", "WWW.Example.com", "api.corp.internal."].into_iter())
+    ///     .suffixes(vec!["CORP.internal."].into_iter())
+    ///     .dedup(true)
+    ///     .normalize(true)
+    ///     .build()
+    ///     .expect("Failed to parse domains.");
+    ///
+    /// let domains: Vec<String> = manager.domains().map(|domain| domain.all().join(".")).collect();
+    ///
+    /// assert_eq!(domains, vec!["www.example.com", "api.corp.internal"]);
+    /// assert_eq!(manager.domains().last().map(|domain| domain.suffix()), Some("corp.internal"));
+    /// ```
+    pub fn normalize(mut self, normalize: bool) -> Self {
+        self.normalize = normalize;
+        self
+    }
+
+    /// Only generates a single shard, see [RipGenManager::with_shard]. With a [WordScope] other
+    /// than [WordScope::Global], the words of each pool are split between the shards instead.
+    ///
+    /// ```
+    /// # use ripgen_lib::{DomainComponents, RipGenIterator, RipGenManager, WordlistIterator, WordScope};
+    /// # use ripgen_lib::shard::Shard;
+    /// # use ripgen_lib::variants::depth_permute_transform;
+    /// # fn dash_transform<'domain>(domain_components: &'domain DomainComponents, words: WordlistIterator<'domain>) -> impl Iterator<Item = String> + 'domain {
+    /// #     let (first, rest) = domain_components.all().split_first().expect("Empty domain.");
+    /// #     words.map(move |word| format!("{}-{}.{}", first, word, rest.join(".")))
+    /// # }
+    /// let generate = |shard: Option<Shard>| {
+    ///     let mut builder = RipGenManager::builder()
+    ///         .domains(vec!["api-dev.corp-a.com", "shop.corp-b.com"].into_iter())
+    ///         .words(vec!["prod", "stage"].into_iter())
+    ///         .word_scope(WordScope::Root);
+    ///
+    ///     if let Some(shard) = shard {
+    ///         builder = builder.shard(shard);
+    ///     }
+    ///
+    ///     let manager = builder.build().expect("Failed to parse domains.");
+    ///
+    ///     let mut names: Vec<String> = manager
+    ///         .transform_per_word(None, depth_permute_transform(1, 0, None))
+    ///         .chain_transform_per_word(None, dash_transform)
+    ///         .collect();
+    ///     names.sort();
+    ///     names
+    /// };
+    ///
+    /// for count in 1 ..= 6 {
+    ///     let mut union: Vec<String> = (1 ..= count)
+    ///         .flat_map(|index| generate(Some(Shard::new(index, count).expect("Invalid shard."))))
+    ///         .collect();
+    ///     union.sort();
+    ///
+    ///     assert_eq!(union, generate(None));
+    /// }
+    /// ```
+    pub fn shard(mut self, shard: Shard) -> Self {
+        self.shard = Some(shard);
+        self
+    }
+
+    /// Creates the manager. This can fail if any of the domains in scope are unable to be parsed.
+    ///
+    /// The scope and deduplication also apply to domains [added](RipGenManager::add_domains) to the
+    /// manager later.
+    pub fn build(self) -> Result<RipGenManager<'domain>, RipGenError> {
+        let mut domain_filter = DomainFilter {
+            scope: self.scope,
+            dedup: self.dedup,
+            normalize: self.normalize,
+            seen: FxHashSet::default()
+        };
+
+        let domain_components: Vec<DomainComponents<'domain>> = self.domains
+            .iter()
+            .copied()
+            .filter_map(|(domain, group)| {
+                let domain = domain_filter.prepare(domain);
+
+                (domain_filter.in_scope(domain) && domain_filter.is_new(domain)).then_some((domain, group))
+            })
+            .map(|(domain, group)| {
+                DomainComponents::with_suffixes(domain, &self.suffixes).map(|mut components| {
+                    components.group = group;
+                    components
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        let word_filters = &self.word_filters;
+        let word_filter = |word: &&str| word_filters.iter().all(|filter| filter(word));
+
+        let manager = RipGenManager::from_components(
+            domain_components,
+            self.words.into_iter(),
+            &word_filter,
+            self.tokenizer,
+            self.word_scope,
+            self.suffixes,
+            domain_filter
+        );
+
+        let manager = manager.with_word_weights(self.word_weights);
+
+        Ok(match self.shard {
+            Some(shard) => manager.with_shard(shard),
+            None => manager
+        })
+    }
+}
+
+/// Decides which input domains a manager takes, both when it's built and when domains are added
+/// later. See [scope](RipGenManagerBuilder::scope), [dedup](RipGenManagerBuilder::dedup) and
+/// [normalize](RipGenManagerBuilder::normalize).
+#[derive(Clone, Debug, Default)]
+pub(crate) struct DomainFilter<'domain> {
+    scope: Vec<String>,
+    dedup: bool,
+    normalize: bool,
+    seen: FxHashSet<Cow<'domain, str>>
+}
+
+impl<'domain> DomainFilter<'domain> {
+    /// Returns the domain as it's parsed, trimmed if normalizing.
+    pub(crate) fn prepare(&self, domain: &'domain str) -> &'domain str {
+        match self.normalize {
+            true => domain.trim().trim_end_matches('.'),
+            false => domain
+        }
+    }
+
+    /// Returns whether a domain isn't empty and is in scope.
+    pub(crate) fn in_scope(&self, domain: &str) -> bool {
+        if domain.is_empty() {
+            return false;
+        }
+
+        if self.scope.is_empty() {
+            return true;
+        }
+
+        let domain = normalized(domain);
+
+        self.scope.iter().any(|scope| {
+            domain == *scope || domain.strip_suffix(scope.as_str()).is_some_and(|rest| rest.ends_with('.'))
+        })
+    }
+
+    /// Returns whether a domain should be taken given the domains taken before it, recording it as
+    /// taken if deduplicating.
+    pub(crate) fn is_new(&mut self, domain: &'domain str) -> bool {
+        !self.dedup || self.seen.insert(normalized(domain))
+    }
+}
+
+/// Returns a name without surrounding whitespace or a trailing dot, in lowercase, so that names can
+/// be compared. Only copies names that aren't lowercase already.
+fn normalized(name: &str) -> Cow<'_, str> {
+    let name = name.trim().trim_end_matches('.');
+
+    match name.bytes().any(|byte| byte.is_ascii_uppercase()) {
+        true => Cow::Owned(name.to_ascii_lowercase()),
+        false => Cow::Borrowed(name)
+    }
+}
@@ -1,7 +1,22 @@
 use std::marker::PhantomData;
+use std::sync::Arc;
 use crate::DomainComponents;
+use crate::checkpoint::{Checkpoint, Resumable};
 use crate::transform::RipGenTransform;
 
+/// Returns the words a domain is permuted with when words are scoped to each domain's root or
+/// input group, see [WordScope](crate::WordScope).
+pub type WordsFor<'manager, 'domain, W> = Arc<dyn Fn(&DomainComponents<'domain>) -> W + Send + Sync + 'manager>;
+
+/// The domains and words of a [Shard](crate::shard::Shard) for transforms that use a single word
+/// for each name, see [RipGenIterator::chain_transform_per_word].
+#[derive(Clone)]
+pub struct WordSplit<'manager, 'domain, D, W> {
+    pub(crate) domains: D,
+    pub(crate) words: W,
+    pub(crate) words_for: Option<WordsFor<'manager, 'domain, W>>
+}
+
 /// Describes an iterator that can be apart of a [RipGenChain](crate::RipGenChain).
 pub trait RipGenIterator<'manager, 'domain, D, W>
     where
@@ -16,6 +31,17 @@ pub trait RipGenIterator<'manager, 'domain, D, W>
     /// Returns an iterator over the underlying wordlist.
     fn get_words_iter(&self) -> W;
 
+    /// Returns the domains and words for transforms that use a single word for each name if they
+    /// differ from the others' because the words are split between [shards](crate::shard::Shard).
+    fn get_word_split(&self) -> Option<WordSplit<'manager, 'domain, D, W>> {
+        None
+    }
+
+    /// Returns the lookup for the words of each domain if they aren't the same for every domain.
+    fn get_words_for(&self) -> Option<WordsFor<'manager, 'domain, W>> {
+        None
+    }
+
     /// Chain this `RipGenIterator` with another `RipGenIterator` over the specified transform.
     ///
     /// ```
@@ -35,7 +61,7 @@ pub trait RipGenIterator<'manager, 'domain, D, W>
     /// let wordlist = vec![];
     /// let wordlist_iter = wordlist.iter().map(|elem| *elem);
     ///
-    /// let manager = RipGenManager::new(domain_iter, wordlist_iter)
+    /// let manager = RipGenManager::new(domain_iter, wordlist_iter, &|_| true)
     ///     .expect("Failed to parse domains.");
     ///
     /// let mut iter = manager
@@ -53,10 +79,78 @@ pub trait RipGenIterator<'manager, 'domain, D, W>
             O: Iterator<Item = String>,
             'domain: 'manager
     {
-        let domain_transform = RipGenTransform::new(self.get_domains_iter(), self.get_words_iter(), transform);
+        self.chain_transform_with_words(None, transform)
+    }
+
+    /// Chain this `RipGenIterator` with another `RipGenIterator` over the specified transform, which
+    /// only gets `words` rather than the words of the chain, e.g. a [WordSource](crate::WordSource)
+    /// or the words of a [Wordlist](crate::wordlist::Wordlist) with a certain tag. Passing `None`
+    /// is the same as [chain_transform](Self::chain_transform). `words` aren't
+    /// [scoped](crate::WordScope), nor split between [shards](crate::shard::Shard).
+    ///
+    /// ```
+    /// # use ripgen_lib::{DomainComponents, RipGenIterator, RipGenManager, WordlistIterator};
+    /// # use ripgen_lib::variants::depth_permute_transform;
+    /// # use ripgen_lib::wordlist::Wordlist;
+    /// # fn dash_transform<'domain>(domain_components: &'domain DomainComponents, words: WordlistIterator<'domain>) -> impl Iterator<Item = String> + 'domain {
+    /// #     let (first, rest) = domain_components.all().split_first().expect("Empty domain.");
+    /// #     words.map(move |word| format!("{}-{}.{}", first, word, rest.join(".")))
+    /// # }
+    /// let wordlist = Wordlist::parse("admin 0.9\neu kind=region").expect("Failed to parse wordlist.");
+    /// let regions: Vec<&str> = wordlist.tagged("kind", "region").collect();
+    ///
+    /// let manager = RipGenManager::new(vec!["api.example.com"].into_iter(), wordlist.words(), &|_| false)
+    ///     .expect("Failed to parse domains.");
+    ///
+    /// let mut names: Vec<String> = manager
+    ///     .transform(depth_permute_transform(1, 0, None))
+    ///     .chain_transform_with_words(regions.iter(), dash_transform)
+    ///     .filter(|name| name.contains('-'))
+    ///     .collect();
+    /// names.sort();
+    ///
+    /// assert_eq!(names, vec!["api-eu.example.com"]);
+    /// ```
+    fn chain_transform_with_words<F, O>(self, words: impl Into<Option<W>>, transform: F) -> RipGenChain<'manager, 'domain, Self, RipGenTransform<'manager, 'domain, F, D, W, O>, D, W>
+        where
+            F: Fn(&'manager DomainComponents<'domain>, W) -> O,
+            O: Iterator<Item = String>,
+            'domain: 'manager
+    {
+        let (words, words_for) = match words.into() {
+            Some(words) => (words, None),
+            None => (self.get_words_iter(), self.get_words_for())
+        };
+
+        let domain_transform = RipGenTransform::new(self.get_domains_iter(), words, transform)
+            .with_words_for(words_for)
+            .with_word_split(self.get_word_split());
 
         RipGenChain::new(self, domain_transform)
     }
+
+    /// Chain this `RipGenIterator` with a transform that uses a single word for each name it
+    /// produces and nothing without words, like [permute](crate::dnsgen::permute_words_transform)
+    /// or [dash](crate::dnsgen::dash_transform). When sharded, the words of such a transform are
+    /// split between the shards along with the domains. Otherwise, and when given `words`, this is
+    /// the same as [chain_transform_with_words](Self::chain_transform_with_words).
+    fn chain_transform_per_word<F, O>(self, words: impl Into<Option<W>>, transform: F) -> RipGenChain<'manager, 'domain, Self, RipGenTransform<'manager, 'domain, F, D, W, O>, D, W>
+        where
+            F: Fn(&'manager DomainComponents<'domain>, W) -> O,
+            O: Iterator<Item = String>,
+            'domain: 'manager
+    {
+        let words = words.into();
+        let per_word = words.is_none();
+
+        let mut chain = self.chain_transform_with_words(words, transform);
+
+        if per_word {
+            chain.right = chain.right.map(RipGenTransform::per_word);
+        }
+
+        chain
+    }
 }
 
 pub struct RipGenChain<'manager, 'domain, L, R, D, W>
@@ -153,4 +247,70 @@ impl<'manager, 'domain, L, R, D, W> RipGenIterator<'manager, 'domain, D, W> for
             }
         }
     }
-}
\ No newline at end of file
+
+    fn get_word_split(&self) -> Option<WordSplit<'manager, 'domain, D, W>> {
+        match self.left {
+            Some(ref inner) => inner.get_word_split(),
+            None => match self.right {
+                Some(ref inner) => inner.get_word_split(),
+                None => panic!("huh")
+            }
+        }
+    }
+
+    fn get_words_for(&self) -> Option<WordsFor<'manager, 'domain, W>> {
+        match self.left {
+            Some(ref inner) => inner.get_words_for(),
+            None => match self.right {
+                Some(ref inner) => inner.get_words_for(),
+                None => panic!("huh")
+            }
+        }
+    }
+}
+
+impl<'manager, 'domain, L, R, D, W> Resumable for RipGenChain<'manager, 'domain, L, R, D, W>
+    where
+        L: RipGenIterator<'manager, 'domain, D, W> + Resumable,
+        R: RipGenIterator<'manager, 'domain, D, W> + Resumable,
+        D: Iterator<Item = &'manager DomainComponents<'domain>> + Clone,
+        W: Iterator<Item = &'manager &'domain str> + Clone,
+        'domain: 'manager
+{
+    const TRANSFORMS: usize = L::TRANSFORMS + R::TRANSFORMS;
+
+    fn checkpoint(&self) -> Checkpoint {
+        if let Some(ref inner) = self.left {
+            return inner.checkpoint();
+        }
+
+        match self.right {
+            Some(ref inner) => {
+                let checkpoint = inner.checkpoint();
+
+                Checkpoint { transform: checkpoint.transform + L::TRANSFORMS, ..checkpoint }
+            },
+            None => Checkpoint { transform: Self::TRANSFORMS, ..Checkpoint::default() }
+        }
+    }
+
+    fn resume(&mut self, checkpoint: Checkpoint) {
+        if checkpoint.transform < L::TRANSFORMS {
+            if let Some(ref mut inner) = self.left {
+                inner.resume(checkpoint);
+            }
+
+            return;
+        }
+
+        self.left = None;
+
+        if checkpoint.transform >= Self::TRANSFORMS {
+            self.right = None;
+        }
+
+        if let Some(ref mut inner) = self.right {
+            inner.resume(Checkpoint { transform: checkpoint.transform - L::TRANSFORMS, ..checkpoint });
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! Records how far generation got so that an interrupted run can be resumed without repeating any
+//! output.
+//!
+//! A [Checkpoint] is stored as a single line holding the transform index, the domain index and the
+//! number of names already taken from that domain's output (e.g. `3 1204 17`).
+
+use std::cell::Cell;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::rc::Rc;
+use std::str::FromStr;
+use crate::RipGenError;
+
+/// A position within a [Resumable] iterator.
+///
+/// ```
+/// # use ripgen_lib::{RipGenIterator, RipGenManager};
+/// # use ripgen_lib::checkpoint::Resumable;
+/// # use ripgen_lib::variants::label_deletion_transform;
+/// # use ripgen_lib::variants::label_reorder_transform;
+/// let domains = vec!["a.b.example.com", "c.d.example.com"];
+/// let manager = RipGenManager::new(domains.into_iter(), std::iter::empty(), &|_| true)
+///     .expect("Failed to parse domains.");
+///
+/// let mut interrupted = manager
+///     .transform(label_deletion_transform)
+///     .chain_transform(label_reorder_transform);
+/// let mut before: Vec<String> = interrupted.by_ref().take(3).collect();
+/// let checkpoint = interrupted.checkpoint();
+///
+/// let mut resumed = manager
+///     .transform(label_deletion_transform)
+///     .chain_transform(label_reorder_transform);
+/// resumed.resume(checkpoint);
+///
+/// before.extend(resumed);
+/// before.sort();
+///
+/// assert_eq!(before, vec!["a.example.com", "b.a.example.com", "b.example.com", "c.example.com", "d.c.example.com", "d.example.com"]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// The index of the transform within the chain.
+    pub transform: usize,
+    /// The index of the domain the transform is working on.
+    pub domain: usize,
+    /// How many names of that domain's output were already taken.
+    pub offset: usize
+}
+
+impl Checkpoint {
+    /// Loads a checkpoint from disk, returning `None` if the file doesn't exist.
+    pub fn load(path: impl AsRef<Path>) -> Result<Option<Self>, RipGenError> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => contents.parse().map(Some),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into())
+        }
+    }
+
+    /// Writes the checkpoint to disk. The file is replaced in one step so that being killed midway
+    /// leaves the previous checkpoint in place.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), RipGenError> {
+        let path = path.as_ref();
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".partial");
+
+        std::fs::write(&partial, format!("{}\n", self))?;
+        std::fs::rename(&partial, path)?;
+
+        Ok(())
+    }
+}
+
+impl Display for Checkpoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.transform, self.domain, self.offset)
+    }
+}
+
+impl FromStr for Checkpoint {
+    type Err = RipGenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || RipGenError::ErrorParsingCheckpoint(s.trim().to_string());
+
+        let fields = s
+            .split_whitespace()
+            .map(|field| field.parse::<usize>().map_err(|_| invalid()))
+            .collect::<Result<Vec<usize>, _>>()?;
+
+        match fields[..] {
+            [transform, domain, offset] => Ok(Self { transform, domain, offset }),
+            _ => Err(invalid())
+        }
+    }
+}
+
+/// An iterator that can record how far it got as a [Checkpoint] and continue from one later. Every
+/// transform started from a [RipGenManager](crate::RipGenManager) is resumable, and so are chains
+/// of them.
+///
+/// Resuming skips the domains before the checkpoint without running any transform over them, so a
+/// transform must produce the same output for a domain regardless of the domains before it. Every
+/// transform in this crate does; one that remembers earlier domains would repeat or lose output.
+pub trait Resumable {
+    /// The number of transforms the iterator runs.
+    const TRANSFORMS: usize;
+
+    /// Returns the position after the last output, for [resuming](Self::resume) later.
+    fn checkpoint(&self) -> Checkpoint;
+
+    /// Skips a new iterator ahead to `checkpoint` so it continues right after the output that was
+    /// taken before the checkpoint.
+    fn resume(&mut self, checkpoint: Checkpoint);
+}
+
+/// Follows the [Checkpoint] of an iterator while its output is consumed elsewhere, such as behind
+/// adapters or a `Box<dyn Iterator>`.
+#[derive(Clone, Debug, Default)]
+pub struct CheckpointTracker {
+    current: Rc<Cell<Checkpoint>>
+}
+
+impl CheckpointTracker {
+    /// Creates a tracker starting from `checkpoint`, usually the one being resumed from.
+    pub fn new(checkpoint: Checkpoint) -> Self {
+        Self {
+            current: Rc::new(Cell::new(checkpoint))
+        }
+    }
+
+    /// Returns the checkpoint after the last name taken from a [tracked](Self::track) iterator.
+    pub fn current(&self) -> Checkpoint {
+        self.current.get()
+    }
+
+    /// Wraps `iter` so that every name taken from it updates the current checkpoint to `checkpoint(&iter)`.
+    pub fn track<I, F>(&self, mut iter: I, checkpoint: F) -> impl Iterator<Item = I::Item>
+        where
+            I: Iterator,
+            F: Fn(&I) -> Checkpoint
+    {
+        let current = self.current.clone();
+
+        std::iter::from_fn(move || {
+            let next = iter.next();
+            current.set(checkpoint(&iter));
+
+            next
+        })
+    }
+}
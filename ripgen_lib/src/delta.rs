@@ -0,0 +1,32 @@
+use std::slice::Iter as SliceIter;
+use crate::DomainComponents;
+use crate::pools::WordPools;
+use crate::source::WordSource;
+use crate::transform::RipGenTransform;
+use crate::words::WordSet;
+
+/// One part of a [delta](crate::RipGenManager::delta): some of the domains paired with every word.
+#[derive(Clone, Copy)]
+pub struct DeltaView<'manager, 'domain> {
+    pub(crate) domains: &'manager [DomainComponents<'domain>],
+    pub(crate) words: &'manager WordSet<'domain>,
+    pub(crate) pools: Option<&'manager WordPools<'domain>>
+}
+
+impl<'manager, 'domain> DeltaView<'manager, 'domain> {
+    /// Begins a RipGen transform iterator over this part, like [RipGenManager::transform](crate::RipGenManager::transform).
+    pub fn transform<F, O>(&self, transform: F) -> RipGenTransform<'manager, 'domain, F, SliceIter<'manager, DomainComponents<'domain>>, SliceIter<'manager, &'domain str>, O>
+        where
+            F: Fn(&'manager DomainComponents<'domain>, SliceIter<'manager, &'domain str>) -> O,
+            O: Iterator<Item = String>,
+            'domain: 'manager
+    {
+        RipGenTransform::new(self.domains.iter(), self.words.iter(), transform)
+            .with_words_for(self.pools.map(WordPools::lookup))
+    }
+
+    /// Returns the words that pass `filter`, like [RipGenManager::word_source](crate::RipGenManager::word_source).
+    pub fn word_source(&self, filter: impl Fn(&&str) -> bool) -> WordSource<'domain> {
+        WordSource { words: self.words.iter().copied().filter(filter).collect() }
+    }
+}
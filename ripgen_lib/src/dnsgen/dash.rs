@@ -5,10 +5,10 @@ pub fn dash_transform<'domain>(
     words: WordlistIterator<'domain>,
 ) -> impl Iterator<Item = String> + 'domain {
     words.flat_map(move |word| {
-        transform_components(domain_components, *word, dash)
-            .chain(transform_components(domain_components, *word, rdash))
-            .chain(transform_components(domain_components, *word, concat))
-            .chain(transform_components(domain_components, *word, rconcat))
+        transform_components(domain_components, word, dash)
+            .chain(transform_components(domain_components, word, rdash))
+            .chain(transform_components(domain_components, word, concat))
+            .chain(transform_components(domain_components, word, rconcat))
     })
 }
 
@@ -5,7 +5,10 @@ use crate::error::RipGenError;
 #[derive(Clone)]
 /// Contains the byproduct of parsing a domain
 pub struct DomainComponents<'domain> {
-    components: Vec<&'domain str>
+    components: Vec<&'domain str>,
+    suffix: &'domain str,
+    /// The input group the domain was given in.
+    pub(crate) group: usize
 }
 
 impl<'domain> DomainComponents<'domain> {
@@ -52,16 +55,93 @@ impl<'domain> DomainComponents<'domain> {
     ///
     /// assert_eq!(domain_component.root(), "google.com");
     /// ```
-    pub fn root(&self) -> &str {
+    pub fn root(&self) -> &'domain str {
         self.components[self.components.len() - 1]
     }
 
+    /// Returns the public suffix of the root
+    ///
+    /// ```
+    /// # use ripgen_lib::DomainComponents;
+    /// # use std::convert::TryFrom;
+    /// let domain_component = DomainComponents::try_from("www.google.co.uk")
+    ///     .expect("Failed to parse.");
+    ///
+    /// assert_eq!(domain_component.suffix(), "co.uk");
+    /// ```
+    pub fn suffix(&self) -> &'domain str {
+        self.suffix
+    }
+
     /// Returns a slice with all domain components in it. This includes the root element.
     pub fn all(&self) -> &[&str] {
         &self.components
     }
 }
 
+impl<'domain> DomainComponents<'domain> {
+    /// Parses a domain like [try_from](Self::try_from), but treats each of `suffixes` as a public
+    /// suffix on top of the public suffix list. The longest matching suffix wins. Suffixes match
+    /// regardless of case and of a trailing dot on either side.
+    ///
+    /// ```
+    /// # use ripgen_lib::DomainComponents;
+    /// let domain_component = DomainComponents::with_suffixes("www.app.corp.internal", &["corp.internal"])
+    ///     .expect("Failed to parse.");
+    ///
+    /// assert_eq!(domain_component.root(), "app.corp.internal");
+    /// assert_eq!(domain_component.suffix(), "corp.internal");
+    ///
+    /// let domain_component = DomainComponents::with_suffixes("www.App.CORP.internal.", &["corp.Internal."])
+    ///     .expect("Failed to parse.");
+    ///
+    /// assert_eq!(domain_component.root(), "App.CORP.internal");
+    /// assert_eq!(domain_component.suffix(), "CORP.internal");
+    /// ```
+    pub fn with_suffixes(domain: &'domain str, suffixes: &[impl AsRef<str>]) -> Result<Self, RipGenError> {
+        let name = domain.trim_end_matches('.');
+
+        let suffix_len = suffixes
+            .iter()
+            .map(|suffix| suffix.as_ref().trim_matches('.'))
+            .filter(|suffix| !suffix.is_empty() && name.len() > suffix.len() + 1)
+            .filter(|suffix| {
+                let suffix_start = name.len() - suffix.len();
+
+                name.get(suffix_start ..).is_some_and(|tail| tail.eq_ignore_ascii_case(suffix))
+                    && name.as_bytes()[suffix_start - 1] == b'.'
+            })
+            .map(str::len)
+            .max();
+
+        let suffix_len = match suffix_len {
+            Some(suffix_len) => suffix_len,
+            None => return Self::try_from(domain)
+        };
+
+        let suffix_start = name.len() - suffix_len;
+        let labels = &name[.. suffix_start - 1];
+        let root_start = labels.rfind('.').map_or(0, |dot| dot + 1);
+
+        Ok(Self::from_parts(name, &name[root_start ..], &name[suffix_start ..]))
+    }
+
+    fn from_parts(domain: &'domain str, root: &'domain str, suffix: &'domain str) -> Self {
+        let components: Vec<&'domain str> = domain
+            .trim_end_matches(root)
+            .split('.')
+            .filter(|elem| !elem.is_empty())
+            .chain(vec![root])
+            .collect::<Vec<&str>>();
+
+        Self {
+            components,
+            suffix,
+            group: 0
+        }
+    }
+}
+
 impl<'domain> TryFrom<&'domain str> for DomainComponents<'domain> {
     type Error = RipGenError;
 
@@ -77,17 +157,13 @@ impl<'domain> TryFrom<&'domain str> for DomainComponents<'domain> {
         let root_start = domain.len() - root.len();
         let root: &'domain str = &domain[root_start..];
 
-        let components: Vec<&'domain str> = domain
-            .trim_end_matches(root)
-            .split('.')
-            .filter(|elem| !elem.is_empty())
-            .chain(vec![root])
-            .collect::<Vec<&str>>();
+        let suffix: &str = parsed_domain_name
+            .suffix()
+            .unwrap_or(root);
 
-        let new_components = Self {
-            components
-        };
+        let suffix_start = domain.len() - suffix.len();
+        let suffix: &'domain str = &domain[suffix_start..];
 
-        Ok(new_components)
+        Ok(Self::from_parts(domain, root, suffix))
     }
 }
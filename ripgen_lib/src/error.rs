@@ -5,4 +5,18 @@ use thiserror::Error;
 pub enum RipGenError {
     #[error("Unable to parse provided domain.")]
     ErrorParsingDomain(String),
-}
\ No newline at end of file
+    #[error("Unable to parse feedback model line: {0}")]
+    ErrorParsingModel(String),
+    #[error("Unable to parse wordlist line: {0}")]
+    ErrorParsingWordlist(String),
+    #[error("Unable to parse checkpoint: {0}")]
+    ErrorParsingCheckpoint(String),
+    #[error("Invalid shard, expected `index/count` with index from 1 to count: {0}")]
+    InvalidShard(String),
+    #[error("No nameservers were provided.")]
+    NoNameservers,
+    #[error("A resolver task failed: {0}")]
+    ResolverTask(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
@@ -0,0 +1,200 @@
+//! Learns which transforms and words produced names that actually resolved, so later runs against
+//! the same target can prioritize or prune them.
+//!
+//! The model is stored as a small text file with one tab-separated `kind`, `key` and `hits` entry
+//! per line, where `kind` is either `transform` or `word` (e.g. `word<TAB>api<TAB>7`).
+
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+use fxhash::{FxHashMap, FxHashSet};
+use crate::{RipGenError, RipGenManager};
+use crate::words::{split_segments, is_token};
+
+/// Per-transform and per-word counts of generated names that were confirmed to resolve.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FeedbackModel {
+    transform_hits: FxHashMap<String, u64>,
+    word_hits: FxHashMap<String, u64>
+}
+
+impl FeedbackModel {
+    /// Loads a model from disk, returning an empty model if the file doesn't exist yet.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RipGenError> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => contents.parse(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into())
+        }
+    }
+
+    /// Writes the model to disk.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), RipGenError> {
+        std::fs::write(path, self.to_string())?;
+
+        Ok(())
+    }
+
+    /// Records the names that were confirmed to resolve, each optionally paired with the transform
+    /// that produced it.
+    ///
+    /// Names that were part of the manager's input are skipped. Every token of a confirmed name
+    /// that is one of the manager's words counts as a hit for that word.
+    ///
+    /// ```
+    /// # use ripgen_lib::RipGenManager;
+    /// # use ripgen_lib::feedback::FeedbackModel;
+    /// let domains = vec!["api.example.com"];
+    /// let words = vec!["dev"];
+    /// let manager = RipGenManager::new(domains.into_iter(), words.into_iter(), &|_| true)
+    ///     .expect("Failed to parse domains.");
+    ///
+    /// let mut model = FeedbackModel::default();
+    /// model.learn(&manager, vec![("dev.api.example.com", Some("permute"))].into_iter());
+    ///
+    /// assert_eq!(model.transform_hits("permute"), 1);
+    /// assert_eq!(model.word_hits("dev"), 1);
+    /// assert_eq!(model.word_hits("api"), 1);
+    /// ```
+    pub fn learn<'a>(
+        &mut self,
+        manager: &RipGenManager,
+        confirmed: impl Iterator<Item = (&'a str, Option<&'a str>)>
+    ) {
+        let inputs: FxHashSet<String> = manager
+            .domains()
+            .map(|domain| domain.all().join("."))
+            .collect();
+
+        let words: FxHashSet<&str> = manager.words().collect();
+
+        for (name, transform) in confirmed {
+            let name = name.trim().trim_end_matches('.');
+
+            if name.is_empty() || inputs.contains(name) {
+                continue;
+            }
+
+            if let Some(transform) = transform {
+                *self.transform_hits.entry(transform.to_string()).or_default() += 1;
+            }
+
+            let mut seen: FxHashSet<&str> = FxHashSet::default();
+            let labels = name.split('.');
+            let tokens = labels
+                .clone()
+                .chain(labels.flat_map(|label| split_segments(label).into_iter().filter(|segment| is_token(segment))));
+
+            for token in tokens {
+                if words.contains(token) && seen.insert(token) {
+                    *self.word_hits.entry(token.to_string()).or_default() += 1;
+                }
+            }
+        }
+    }
+
+    /// Returns the number of confirmed names produced by the transform.
+    pub fn transform_hits(&self, transform: &str) -> u64 {
+        self.transform_hits.get(transform).copied().unwrap_or(0)
+    }
+
+    /// Returns the number of confirmed names that contained the word.
+    pub fn word_hits(&self, word: &str) -> u64 {
+        self.word_hits.get(word).copied().unwrap_or(0)
+    }
+
+    /// Returns every word with at least one confirmed name and its hits.
+    pub fn words(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.word_hits.iter().map(|(word, hits)| (word.as_str(), *hits))
+    }
+
+    /// Returns whether anything has been learned yet.
+    pub fn is_empty(&self) -> bool {
+        self.transform_hits.is_empty() && self.word_hits.is_empty()
+    }
+
+    /// Adjusts a transform's `default_prior` by how productive it has been relative to the most
+    /// productive transform. Without any recorded transform hits the default is returned.
+    pub fn transform_prior(&self, transform: &str, default_prior: f64) -> f64 {
+        let max_hits = match self.transform_hits.values().max() {
+            Some(max_hits) => *max_hits,
+            None => return default_prior
+        };
+
+        // smoothed so that transforms without hits are deprioritized rather than excluded
+        let learned = (self.transform_hits(transform) + 1) as f64 / (max_hits + 1) as f64;
+
+        default_prior * learned
+    }
+
+    /// Returns whether the transform should still run: either nothing has been learned about
+    /// transforms yet or it has produced at least one confirmed name.
+    pub fn is_productive(&self, transform: &str) -> bool {
+        self.transform_hits.is_empty() || self.transform_hits(transform) > 0
+    }
+
+    /// Returns whether the word should still be used: either nothing has been learned about words
+    /// yet or it was part of at least one confirmed name.
+    ///
+    /// ```
+    /// # use ripgen_lib::feedback::FeedbackModel;
+    /// let model: FeedbackModel = "word\tapi\t3\n".parse().expect("Failed to parse model.");
+    ///
+    /// assert!(model.is_productive_word("api"));
+    /// assert!(!model.is_productive_word("legacy"));
+    /// assert!(FeedbackModel::default().is_productive_word("legacy"));
+    /// ```
+    pub fn is_productive_word(&self, word: &str) -> bool {
+        self.word_hits.is_empty() || self.word_hits(word) > 0
+    }
+}
+
+impl Display for FeedbackModel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // sorted so the file diffs cleanly between runs
+        let mut transforms: Vec<_> = self.transform_hits.iter().collect();
+        transforms.sort();
+
+        let mut words: Vec<_> = self.word_hits.iter().collect();
+        words.sort();
+
+        for (transform, hits) in transforms {
+            writeln!(f, "transform\t{transform}\t{hits}")?;
+        }
+
+        for (word, hits) in words {
+            writeln!(f, "word\t{word}\t{hits}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for FeedbackModel {
+    type Err = RipGenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut model = Self::default();
+
+        for line in s.lines().filter(|line| !line.trim().is_empty()) {
+            let fields: Vec<&str> = line.split('\t').collect();
+
+            let (kind, key, hits) = match fields.as_slice() {
+                [kind, key, hits] => (*kind, *key, hits.parse::<u64>()),
+                _ => return Err(RipGenError::ErrorParsingModel(line.to_string()))
+            };
+
+            let hits = hits.map_err(|_| RipGenError::ErrorParsingModel(line.to_string()))?;
+
+            let counts = match kind {
+                "transform" => &mut model.transform_hits,
+                "word" => &mut model.word_hits,
+                _ => return Err(RipGenError::ErrorParsingModel(line.to_string()))
+            };
+
+            *counts.entry(key.to_string()).or_default() += hits;
+        }
+
+        Ok(model)
+    }
+}
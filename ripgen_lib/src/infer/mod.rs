@@ -0,0 +1,158 @@
+//! Learns the naming patterns of a target from its known subdomains and generates the names that
+//! fit those patterns.
+//!
+//! Names are split into tokens on `.`, `-` and runs of digits. Names under the same root with the
+//! same token structure are grouped into a [Template] where every position that varies becomes a
+//! [Slot] holding the values that were seen there (e.g. `api-{dev,prod}-{1..3}`).
+//!
+//! ```
+//! # use ripgen_lib::RipGenManager;
+//! # use ripgen_lib::infer::PatternModel;
+//! let domains = vec!["api-dev-1.example.com", "api-prod-3.example.com", "www.example.com"];
+//! let manager = RipGenManager::new(domains.into_iter(), std::iter::empty(), &|_| true)
+//!     .expect("Failed to parse domains.");
+//!
+//! let model = PatternModel::learn(manager.domains());
+//! let mut output: Vec<String> = model.generate(100).collect();
+//! output.sort();
+//!
+//! assert_eq!(output, vec![
+//!     "api-dev-2.example.com",
+//!     "api-dev-3.example.com",
+//!     "api-prod-1.example.com",
+//!     "api-prod-2.example.com"
+//! ]);
+//! ```
+
+mod template;
+
+pub use template::{Slot, Template};
+
+use fxhash::{FxHashMap, FxHashSet};
+use crate::DomainComponents;
+use crate::words::{split_segments, is_token};
+
+/// The templates learned from a set of domains, ordered from most to least supported.
+#[derive(Clone, Debug, Default)]
+pub struct PatternModel {
+    templates: Vec<Template>
+}
+
+impl PatternModel {
+    /// Learns templates from the subdomains of every domain. Apex domains are ignored and templates
+    /// only supported by a single name are dropped as there is nothing to generalize from.
+    pub fn learn<'manager, 'domain: 'manager>(
+        domains: impl Iterator<Item = &'manager DomainComponents<'domain>>
+    ) -> Self {
+        let mut groups: FxHashMap<GroupKey, Vec<(Vec<&str>, String)>> = FxHashMap::default();
+        let mut group_order: Vec<GroupKey> = Vec::new();
+
+        for domain in domains {
+            if domain.subdomains().is_empty() {
+                continue;
+            }
+
+            let segments = name_segments(domain.subdomains());
+            let shape: Vec<SegmentShape> = segments.iter().map(|segment| SegmentShape::from(*segment)).collect();
+            let key = (domain.root(), shape);
+
+            let name = domain.all().join(".");
+            let group = groups.entry(key.clone()).or_insert_with(|| {
+                group_order.push(key);
+                Vec::new()
+            });
+
+            group.push((segments, name));
+        }
+
+        let mut templates: Vec<Template> = group_order
+            .into_iter()
+            .filter_map(|key| {
+                let names = groups.remove(&key)?;
+                let (root, shape) = key;
+
+                let observed: FxHashSet<String> = names.iter().map(|(_, name)| name.clone()).collect();
+
+                if observed.len() < 2 {
+                    return None;
+                }
+
+                let slots = shape
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, segment_shape)| {
+                        let mut seen: FxHashSet<&str> = FxHashSet::default();
+                        let values: Vec<String> = names
+                            .iter()
+                            .map(|(segments, _)| segments[idx])
+                            .filter(|value| seen.insert(value))
+                            .map(str::to_string)
+                            .collect();
+
+                        match (values.len(), segment_shape) {
+                            (1, _) => Slot::Literal(values[0].clone()),
+                            (_, SegmentShape::Number) => Slot::Numbers(values),
+                            _ => Slot::Words(values)
+                        }
+                    })
+                    .collect();
+
+                Some(Template { root: root.to_string(), slots, observed })
+            })
+            .collect();
+
+        // stable so that equally supported templates keep the order they were first seen in
+        templates.sort_by_key(|template| std::cmp::Reverse(template.support()));
+
+        Self { templates }
+    }
+
+    /// Returns the learned templates, from most to least supported.
+    pub fn templates(&self) -> &[Template] {
+        &self.templates
+    }
+
+    /// Generates up to `per_template_limit` unobserved names from each template, starting with the
+    /// most supported template.
+    pub fn generate(&self, per_template_limit: usize) -> impl Iterator<Item = String> + '_ {
+        self.templates
+            .iter()
+            .flat_map(move |template| template.generate(per_template_limit))
+    }
+}
+
+/// Names are grouped by their root and the shape of their segments.
+type GroupKey<'domain> = (&'domain str, Vec<SegmentShape>);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum SegmentShape {
+    Word,
+    Number,
+    Separator(char)
+}
+
+impl From<&str> for SegmentShape {
+    fn from(segment: &str) -> Self {
+        match segment {
+            "." => SegmentShape::Separator('.'),
+            segment if segment.starts_with('-') => SegmentShape::Separator('-'),
+            segment if is_token(segment) => SegmentShape::Word,
+            _ => SegmentShape::Number
+        }
+    }
+}
+
+/// Splits every label into segments, with a `.` segment between labels.
+fn name_segments<'domain>(labels: &[&'domain str]) -> Vec<&'domain str> {
+    let mut segments = Vec::new();
+
+    for (idx, label) in labels.iter().enumerate() {
+        if idx > 0 {
+            segments.push(".");
+        }
+
+        segments.extend(split_segments(label));
+    }
+
+    segments
+}
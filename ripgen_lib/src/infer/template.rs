@@ -0,0 +1,153 @@
+use std::fmt::{Display, Formatter};
+use fxhash::FxHashSet;
+
+/// The largest numeric range that will be filled in between the smallest and largest observed numbers.
+const MAX_NUMBER_RANGE: u64 = 1000;
+
+/// A single position within a [Template].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Slot {
+    /// A separator or token that was identical in every name of the template.
+    Literal(String),
+    /// A token that took different values across the names of the template.
+    Words(Vec<String>),
+    /// A number that took different values across the names of the template. Generation fills in
+    /// the range between the smallest and largest observed numbers, keeping any zero padding.
+    Numbers(Vec<String>)
+}
+
+impl Slot {
+    /// Returns every value this slot can take during generation.
+    pub fn values(&self) -> Vec<String> {
+        match self {
+            Slot::Literal(value) => vec![value.clone()],
+            Slot::Words(values) => values.clone(),
+            Slot::Numbers(values) => expand_numbers(values)
+        }
+    }
+}
+
+/// A naming pattern learned from names that share the same structure under the same root, e.g.
+/// `api-{dev,prod}-{1..3}.example.com`.
+#[derive(Clone, Debug)]
+pub struct Template {
+    pub(crate) root: String,
+    pub(crate) slots: Vec<Slot>,
+    pub(crate) observed: FxHashSet<String>
+}
+
+impl Template {
+    /// Returns the root the template was learned under.
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
+    /// Returns the slots that make up the subdomain part of the template.
+    pub fn slots(&self) -> &[Slot] {
+        &self.slots
+    }
+
+    /// Returns the number of observed names that fit this template.
+    pub fn support(&self) -> usize {
+        self.observed.len()
+    }
+
+    /// Returns up to `limit` names that fit the template but were not observed.
+    pub fn generate(&self, limit: usize) -> Vec<String> {
+        let slot_values: Vec<Vec<String>> = self.slots
+            .iter()
+            .map(Slot::values)
+            .collect();
+
+        let mut results = Vec::new();
+
+        if slot_values.iter().any(Vec::is_empty) {
+            return results;
+        }
+
+        // walk the cartesian product of every slot's values like an odometer
+        let mut indices = vec![0; slot_values.len()];
+
+        loop {
+            if results.len() >= limit {
+                break;
+            }
+
+            let subdomain: String = indices
+                .iter()
+                .zip(&slot_values)
+                .map(|(idx, values)| values[*idx].as_str())
+                .collect();
+
+            let name = format!("{}.{}", subdomain, self.root);
+
+            if !self.observed.contains(&name) {
+                results.push(name);
+            }
+
+            let mut position = indices.len();
+
+            loop {
+                if position == 0 {
+                    return results;
+                }
+
+                position -= 1;
+                indices[position] += 1;
+
+                if indices[position] < slot_values[position].len() {
+                    break;
+                }
+
+                indices[position] = 0;
+            }
+        }
+
+        results
+    }
+}
+
+impl Display for Template {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for slot in &self.slots {
+            match slot {
+                Slot::Literal(value) => write!(f, "{value}")?,
+                Slot::Words(values) => write!(f, "{{{}}}", values.join(","))?,
+                Slot::Numbers(values) => {
+                    let expanded = expand_numbers(values);
+
+                    match (expanded.first(), expanded.last()) {
+                        (Some(first), Some(last)) if expanded.len() > values.len() => write!(f, "{{{first}..{last}}}")?,
+                        _ => write!(f, "{{{}}}", values.join(","))?
+                    }
+                }
+            }
+        }
+
+        write!(f, ".{} ({})", self.root, self.support())
+    }
+}
+
+/// Fills in the range between the smallest and largest number, padding to the observed width if
+/// every observed number had the same width.
+fn expand_numbers(values: &[String]) -> Vec<String> {
+    let numbers: Vec<u64> = values
+        .iter()
+        .filter_map(|value| value.parse().ok())
+        .collect();
+
+    let (min, max) = match (numbers.iter().min(), numbers.iter().max()) {
+        (Some(min), Some(max)) if max - min <= MAX_NUMBER_RANGE => (*min, *max),
+        _ => return values.to_vec()
+    };
+
+    let width = values[0].len();
+    let padded = values.iter().all(|value| value.len() == width);
+
+    (min ..= max)
+        .map(|number| match padded {
+            true => format!("{number:0width$}"),
+            false => number.to_string()
+        })
+        .collect()
+}
@@ -2,21 +2,55 @@
 
 pub use chain::{
     RipGenChain,
-    RipGenIterator
+    RipGenIterator,
+    WordSplit,
+    WordsFor
 };
 pub use domain::DomainComponents;
 pub use error::RipGenError;
 pub use manager::RipGenManager;
+pub use builder::RipGenManagerBuilder;
+pub use delta::DeltaView;
+pub use words::{Tokenizer, WordScope};
+pub use source::WordSource;
+pub use owned::{BoxedChain, OwnedRipGenChain, OwnedRipGenManager};
 
 mod manager;
+mod builder;
+mod delta;
+mod owned;
 mod domain;
 mod error;
 mod words;
+mod pools;
+mod source;
 mod chain;
 pub(crate) mod transform;
 
+pub mod variants;
+pub mod infer;
+pub mod markov;
+pub mod rank;
+pub mod feedback;
+pub mod rounds;
+pub mod shard;
+pub mod checkpoint;
+pub mod wordlist;
+
 #[cfg(feature = "dnsgen")]
 pub mod dnsgen;
 
+#[cfg(feature = "altdns")]
+pub mod altdns;
+
+#[cfg(feature = "typo")]
+pub mod typo;
+
+#[cfg(feature = "resolve")]
+pub mod resolve;
+
+#[cfg(feature = "async")]
+pub mod stream;
+
 /// Placeholder for a HashSet iterator with annoying lifetimes
-pub type WordlistIterator<'domain> = std::collections::hash_set::Iter<'domain, &'domain str>;
\ No newline at end of file
+pub type WordlistIterator<'domain> = std::slice::Iter<'domain, &'domain str>;
\ No newline at end of file
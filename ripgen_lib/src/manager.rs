@@ -1,22 +1,44 @@
 use crate::domain::DomainComponents;
 use crate::error::RipGenError;
-use fxhash::FxHashSet;
+use fxhash::{FxHashMap, FxHashSet};
 use std::slice::Iter as SliceIter;
-use std::collections::hash_set::Iter as HashSetIter;
 use crate::transform::RipGenTransform;
+use crate::shard::{Shard, ShardSlice};
+use crate::words::{Tokenizer, WordScope, WordSet};
+use crate::pools::WordPools;
+use crate::RipGenManagerBuilder;
+use crate::builder::DomainFilter;
+use crate::chain::WordSplit;
+use crate::delta::DeltaView;
+use crate::source::WordSource;
 
 #[derive(Clone)]
 /// Processes and manages domains and wordlist elements to enable creating [RipGenIterator](crate::RipGenIterator)
 /// via [transformations](Self::transform).
 pub struct RipGenManager<'domains> {
     domain_components: Vec<DomainComponents<'domains>>,
-    elements: FxHashSet<&'domains str>,
+    elements: WordSet<'domains>,
+    /// The weight of each word, which orders the words. Words without a weight have a weight of 1.
+    word_weights: FxHashMap<&'domains str, f64>,
+    /// The words of each root or input group, if words are scoped.
+    pools: Option<WordPools<'domains>>,
+    shard: Option<ShardSlice<'domains>>,
+    tokenizer: Tokenizer,
+    suffixes: Vec<String>,
+    domain_filter: DomainFilter<'domains>,
+    /// The number of domains, from the start, that were covered by earlier generations.
+    generated_domains: usize,
+    /// Whether words were added since the last generation.
+    words_added: bool,
+    /// Every name taken from a [delta](Self::delta).
+    generated: FxHashSet<String>
 }
 
 impl<'domain> RipGenManager<'domain> {
     /// Creates a new `RipGenManager`.
     ///
-    /// This can fail if any of the `domains` are unable to be parsed.
+    /// This can fail if any of the `domains` are unable to be parsed. See [builder](Self::builder)
+    /// for more options.
     pub fn new(
         domains: impl Iterator<Item=&'domain str>,
         words: impl Iterator<Item=&'domain str>,
@@ -28,28 +50,418 @@ impl<'domain> RipGenManager<'domain> {
             .map(DomainComponents::try_from)
             .collect::<Result<_, _>>()?;
 
-        let elements: FxHashSet<&'domain str> = crate::words::extract_words(domain_components.iter(), word_filter)
-            .chain(words)
+        Ok(Self::from_components(domain_components, words, word_filter, Tokenizer::default(), WordScope::default(), Vec::new(), DomainFilter::default()))
+    }
+
+    /// Returns a [RipGenManagerBuilder](crate::RipGenManagerBuilder) for configuring a manager
+    /// beyond what [new](Self::new) offers.
+    pub fn builder() -> RipGenManagerBuilder<'domain> {
+        RipGenManagerBuilder::default()
+    }
+
+    pub(crate) fn from_components(
+        domain_components: Vec<DomainComponents<'domain>>,
+        words: impl Iterator<Item=&'domain str>,
+        word_filter: &impl Fn(&&str) -> bool,
+        tokenizer: Tokenizer,
+        word_scope: WordScope,
+        suffixes: Vec<String>,
+        domain_filter: DomainFilter<'domain>
+    ) -> RipGenManager<'domain>
+    {
+        let words: Vec<&'domain str> = words.collect();
+
+        let elements: WordSet<'domain> = crate::words::extract_words(domain_components.iter(), word_filter, tokenizer)
+            .chain(words.iter().copied())
             .collect();
 
-        let manager = RipGenManager {
+        let pools = (word_scope != WordScope::Global).then(|| {
+            let mut pools = WordPools::new(word_scope, words.into_iter().collect());
+
+            for domain in domain_components.iter() {
+                pools.add_domain_words(domain, crate::words::extract_words(std::iter::once(domain), word_filter, tokenizer));
+            }
+
+            pools
+        });
+
+        RipGenManager {
             domain_components,
-            elements
+            elements,
+            word_weights: FxHashMap::default(),
+            pools,
+            shard: None,
+            tokenizer,
+            suffixes,
+            domain_filter,
+            generated_domains: 0,
+            words_added: false,
+            generated: FxHashSet::default()
+        }
+    }
+
+    /// Adds domains after the manager was created, along with the words extracted from them that
+    /// pass `word_filter`. Domains out of the [builder's](Self::builder) scope, or already added if
+    /// it deduplicates, are skipped, and they're trimmed if it normalizes.
+    ///
+    /// This can fail if any of the `domains` are unable to be parsed, in which case none are added.
+    ///
+    /// ```
+    /// # use ripgen_lib::RipGenManager;
+    /// let mut manager = RipGenManager::builder()
+    ///     .domains(vec!["www.example.com"].into_iter())
+    ///     .scope(vec!["example.com"].into_iter())
+    ///     .dedup(true)
+    ///     .build()
+    ///     .expect("Failed to parse domains.");
+    ///
+    /// manager.add_domains(vec!["www.example.com", "api.example.com", "www.other.com"].into_iter(), &|_| true)
+    ///     .expect("Failed to parse domains.");
+    ///
+    /// assert_eq!(manager.domains().count(), 2);
+    /// ```
+    pub fn add_domains(
+        &mut self,
+        domains: impl Iterator<Item=&'domain str>,
+        word_filter: &impl Fn(&&str) -> bool
+    ) -> Result<(), RipGenError>
+    {
+        let domains: Vec<&'domain str> = domains
+            .map(|domain| self.domain_filter.prepare(domain))
+            .filter(|domain| self.domain_filter.in_scope(domain))
+            .collect();
+
+        let parsed: Vec<DomainComponents> = domains
+            .iter()
+            .map(|domain| DomainComponents::with_suffixes(domain, &self.suffixes))
+            .collect::<Result<_, _>>()?;
+
+        // only checked once every domain parsed, so that a failed call doesn't count as seeing them
+        let added: Vec<DomainComponents> = domains
+            .into_iter()
+            .zip(parsed)
+            .filter(|(domain, _)| self.domain_filter.is_new(domain))
+            .map(|(_, components)| components)
+            .collect();
+
+        let words: Vec<&'domain str> = crate::words::extract_words(added.iter(), word_filter, self.tokenizer).collect();
+
+        if let Some(ref mut pools) = self.pools {
+            for domain in added.iter() {
+                pools.add_domain_words(domain, crate::words::extract_words(std::iter::once(domain), word_filter, self.tokenizer));
+            }
+        }
+
+        self.domain_components.extend(added);
+        self.extend_elements(words);
+
+        Ok(())
+    }
+
+    /// Adds wordlist entries after the manager was created.
+    pub fn add_words(&mut self, words: impl Iterator<Item=&'domain str>) {
+        let words: Vec<&'domain str> = words.collect();
+
+        if let Some(ref mut pools) = self.pools {
+            pools.add_wordlist(&words);
+        }
+
+        self.extend_elements(words);
+    }
+
+    fn extend_elements(&mut self, words: Vec<&'domain str>) {
+        for word in words {
+            self.words_added |= self.elements.insert(word);
+        }
+
+        self.order_words();
+
+        if let Some(ref slice) = self.shard {
+            self.shard = Some(slice.shard.slice(&self.domain_components, &self.elements, self.pools.as_ref()));
+        }
+    }
+
+    /// Sets the weight of each word, which transforms try the words in from highest to lowest. Words
+    /// without a weight have a weight of 1 and words with the same weight keep their order.
+    pub(crate) fn with_word_weights(mut self, word_weights: FxHashMap<&'domain str, f64>) -> Self {
+        self.word_weights = word_weights;
+        self.order_words();
+        self
+    }
+
+    fn order_words(&mut self) {
+        if self.word_weights.is_empty() {
+            return;
+        }
+
+        self.elements.order_by(&self.word_weights);
+
+        if let Some(ref mut pools) = self.pools {
+            pools.order_by(&self.word_weights);
+        }
+    }
+
+    /// Runs the chain built by `build` over what changed since the last call to
+    /// [mark_generated](Self::mark_generated): the new domains, then the earlier domains if any
+    /// words were added. Every domain is paired with all of its words and names already taken from
+    /// an earlier delta are skipped, so each name comes out once, including names built from
+    /// several words or produced from more than one domain.
+    ///
+    /// `build` is called once for each of the two parts. Every name taken from a delta is
+    /// remembered, which takes memory in proportion to the output. Sharding doesn't apply to the
+    /// delta.
+    ///
+    /// ```
+    /// # use ripgen_lib::{RipGenIterator, RipGenManager};
+    /// # use ripgen_lib::variants::{depth_permute_transform, label_deletion_transform};
+    /// let mut manager = RipGenManager::new(vec!["a.b.example.com"].into_iter(), vec!["dev"].into_iter(), &|_| false)
+    ///     .expect("Failed to parse domains.");
+    ///
+    /// let mut first: Vec<String> = manager
+    ///     .delta(|view| view.transform(depth_permute_transform(1, 0, None)).chain_transform(label_deletion_transform))
+    ///     .collect();
+    /// first.sort();
+    ///
+    /// assert_eq!(first, vec!["a.b.dev.example.com", "a.dev.b.example.com", "a.example.com", "b.example.com", "dev.a.b.example.com"]);
+    ///
+    /// manager.mark_generated();
+    /// manager.add_words(vec!["qa"].into_iter());
+    ///
+    /// let mut second: Vec<String> = manager
+    ///     .delta(|view| view.transform(depth_permute_transform(1, 0, None)).chain_transform(label_deletion_transform))
+    ///     .collect();
+    /// second.sort();
+    ///
+    /// assert_eq!(second, vec!["a.b.qa.example.com", "a.qa.b.example.com", "qa.a.b.example.com"]);
+    ///
+    /// manager.mark_generated();
+    /// manager.add_domains(vec!["c.example.com", "x.b.example.com"].into_iter(), &|_| false).expect("Failed to parse domains.");
+    ///
+    /// let mut third: Vec<String> = manager
+    ///     .delta(|view| view.transform(depth_permute_transform(1, 0, None)).chain_transform(label_deletion_transform))
+    ///     .collect();
+    /// third.sort();
+    ///
+    /// // b.example.com was already generated from a.b.example.com
+    /// assert_eq!(third, vec![
+    ///     "c.dev.example.com", "c.qa.example.com", "dev.c.example.com", "dev.x.b.example.com", "qa.c.example.com",
+    ///     "qa.x.b.example.com", "x.b.dev.example.com", "x.b.qa.example.com", "x.dev.b.example.com", "x.example.com",
+    ///     "x.qa.b.example.com"
+    /// ]);
+    /// ```
+    ///
+    /// Names built from several words are generated as soon as any of their words is new:
+    ///
+    /// ```
+    /// # use ripgen_lib::RipGenManager;
+    /// # use ripgen_lib::variants::depth_permute_transform;
+    /// let mut manager = RipGenManager::new(vec!["a.example.com"].into_iter(), vec!["dev"].into_iter(), &|_| false)
+    ///     .expect("Failed to parse domains.");
+    ///
+    /// let first: Vec<String> = manager
+    ///     .delta(|view| view.transform(depth_permute_transform(2, 0, None)))
+    ///     .collect();
+    ///
+    /// manager.mark_generated();
+    /// manager.add_words(vec!["qa"].into_iter());
+    ///
+    /// let second: Vec<String> = manager
+    ///     .delta(|view| view.transform(depth_permute_transform(2, 0, None)))
+    ///     .collect();
+    ///
+    /// assert!(first.iter().all(|name| !name.contains("qa")));
+    /// assert!(second.iter().all(|name| name.contains("qa")));
+    /// assert!(second.contains(&"a.qa.dev.example.com".to_string()));
+    /// assert!(second.contains(&"qa.dev.a.example.com".to_string()));
+    /// ```
+    pub fn delta<'manager, F, I>(&'manager mut self, build: F) -> impl Iterator<Item = String> + 'manager
+        where
+            F: Fn(DeltaView<'manager, 'domain>) -> I,
+            I: Iterator<Item = String> + 'manager
+    {
+        let (earlier, added) = self.domain_components.split_at(self.generated_domains.min(self.domain_components.len()));
+
+        // without new words, the earlier domains produce exactly what they did before
+        let earlier = match self.words_added {
+            true => earlier,
+            false => &earlier[.. 0]
         };
 
-        Ok(manager)
+        let added = build(DeltaView {
+            domains: added,
+            words: &self.elements,
+            pools: self.pools.as_ref()
+        });
+
+        let earlier = build(DeltaView {
+            domains: earlier,
+            words: &self.elements,
+            pools: self.pools.as_ref()
+        });
+
+        let generated = &mut self.generated;
+
+        added
+            .chain(earlier)
+            .filter(move |name| !generated.contains(name) && generated.insert(name.clone()))
+    }
+
+    /// Marks every domain and word as generated, so the next [delta](Self::delta) only covers what
+    /// is added from now on.
+    pub fn mark_generated(&mut self) {
+        self.generated_domains = self.domain_components.len();
+        self.words_added = false;
+    }
+
+    /// Returns an iterator over the parsed input domains.
+    pub fn domains(&self) -> SliceIter<'_, DomainComponents<'domain>> {
+        self.domain_components.iter()
+    }
+
+    /// Returns an iterator over every word, both extracted from the domains and from the wordlist, in
+    /// the order transforms try them in.
+    /// This includes the words of every root or input group if words are [scoped](crate::WordScope).
+    pub fn words(&self) -> impl Iterator<Item = &'domain str> + '_ {
+        self.elements.iter().copied()
+    }
+
+    /// Returns every distinct root seen in the input domains, in the order they first appeared.
+    pub fn roots(&self) -> Vec<&'domain str> {
+        let mut seen: FxHashSet<&'domain str> = FxHashSet::default();
+
+        self.domain_components
+            .iter()
+            .map(DomainComponents::root)
+            .filter(|root| seen.insert(root))
+            .collect()
+    }
+
+    /// Restricts the output of [transforms](Self::transform) to a single [Shard](crate::shard::Shard)
+    /// of the domains and words. [domains](Self::domains), [words](Self::words) and [roots](Self::roots)
+    /// still return everything so that anything learned from them is the same for every shard.
+    ///
+    /// ```
+    /// # use ripgen_lib::{RipGenIterator, RipGenManager};
+    /// # use ripgen_lib::shard::Shard;
+    /// # use ripgen_lib::variants::label_deletion_transform;
+    /// let domains = vec!["a.b.example.com", "c.d.example.com"];
+    ///
+    /// let mut names: Vec<String> = (1 ..= 2)
+    ///     .flat_map(|index| {
+    ///         let manager = RipGenManager::new(domains.iter().copied(), std::iter::empty(), &|_| true)
+    ///             .expect("Failed to parse domains.")
+    ///             .with_shard(Shard::new(index, 2).expect("Invalid shard."));
+    ///
+    ///         manager.transform(label_deletion_transform).collect::<Vec<String>>()
+    ///     })
+    ///     .collect();
+    /// names.sort();
+    ///
+    /// assert_eq!(names, vec!["a.example.com", "b.example.com", "c.example.com", "d.example.com"]);
+    /// ```
+    pub fn with_shard(mut self, shard: Shard) -> Self {
+        self.shard = Some(shard.slice(&self.domain_components, &self.elements, self.pools.as_ref()));
+        self
     }
 
     /// Begins a RipGen transform iterator.
     ///
     /// Requires a function that can take both a reference to a [DomainComponents](crate::DomainComponents)
     /// as well as an iterator that produces `&&str`.
-    pub fn transform<'manager, F, O>(&'manager self, transform: F) -> RipGenTransform<'manager, 'domain, F, SliceIter<DomainComponents<'domain>>, HashSetIter<'manager, &'domain str>, O>
+    pub fn transform<'manager, F, O>(&'manager self, transform: F) -> RipGenTransform<'manager, 'domain, F, SliceIter<'manager, DomainComponents<'domain>>, SliceIter<'manager, &'domain str>, O>
+        where
+            F: Fn(&'manager DomainComponents<'domain>, SliceIter<'manager, &'domain str>) -> O,
+            O: Iterator<Item = String>,
+            'domain: 'manager
+    {
+        let domains = match self.shard {
+            Some(ref shard) => shard.domains.iter(),
+            None => self.domain_components.iter()
+        };
+
+        RipGenTransform::new(domains, self.elements.iter(), transform)
+            .with_words_for(self.pools.as_ref().map(|pools| pools.lookup()))
+            .with_word_split(self.word_split())
+    }
+
+    /// Begins a RipGen transform iterator like [transform](Self::transform), but the transform
+    /// only gets `words` if they're given. See [RipGenIterator::chain_transform_with_words](crate::RipGenIterator::chain_transform_with_words).
+    pub fn transform_with_words<'manager, F, O>(&'manager self, words: impl Into<Option<SliceIter<'manager, &'domain str>>>, transform: F) -> RipGenTransform<'manager, 'domain, F, SliceIter<'manager, DomainComponents<'domain>>, SliceIter<'manager, &'domain str>, O>
         where
-            F: Fn(&'manager DomainComponents<'domain>, HashSetIter<'manager, &'domain str>) -> O,
+            F: Fn(&'manager DomainComponents<'domain>, SliceIter<'manager, &'domain str>) -> O,
             O: Iterator<Item = String>,
             'domain: 'manager
     {
-        RipGenTransform::new(self.domain_components.iter(), self.elements.iter(), transform)
+        let words = match words.into() {
+            Some(words) => words,
+            None => return self.transform(transform)
+        };
+
+        let domains = match self.shard {
+            Some(ref shard) => shard.domains.iter(),
+            None => self.domain_components.iter()
+        };
+
+        RipGenTransform::new(domains, words, transform)
+            .with_word_split(self.word_split())
+    }
+
+    /// Begins a RipGen transform iterator like [transform_with_words](Self::transform_with_words)
+    /// for a transform that uses a single word for each name. See [RipGenIterator::chain_transform_per_word](crate::RipGenIterator::chain_transform_per_word).
+    pub fn transform_per_word<'manager, F, O>(&'manager self, words: impl Into<Option<SliceIter<'manager, &'domain str>>>, transform: F) -> RipGenTransform<'manager, 'domain, F, SliceIter<'manager, DomainComponents<'domain>>, SliceIter<'manager, &'domain str>, O>
+        where
+            F: Fn(&'manager DomainComponents<'domain>, SliceIter<'manager, &'domain str>) -> O,
+            O: Iterator<Item = String>,
+            'domain: 'manager
+    {
+        match words.into() {
+            Some(words) => self.transform_with_words(words, transform),
+            None => self.transform(transform).per_word()
+        }
+    }
+
+    fn word_split<'manager>(&'manager self) -> Option<WordSplit<'manager, 'domain, SliceIter<'manager, DomainComponents<'domain>>, SliceIter<'manager, &'domain str>>> {
+        self.shard.as_ref().map(|shard| WordSplit {
+            domains: shard.per_word_domains.iter(),
+            words: shard.per_word_words.iter(),
+            words_for: shard.per_word_pools.as_ref().map(|pools| pools.lookup())
+        })
+    }
+
+    /// Returns the manager's words that pass `filter`, to pair a transform with through
+    /// [transform_with_words](Self::transform_with_words) or [chain_transform_with_words](crate::RipGenIterator::chain_transform_with_words).
+    /// The words aren't [scoped](crate::WordScope).
+    ///
+    /// ```
+    /// # use ripgen_lib::{DomainComponents, RipGenIterator, RipGenManager, WordlistIterator};
+    /// # use ripgen_lib::variants::depth_permute_transform;
+    /// # fn dash_transform<'domain>(domain_components: &'domain DomainComponents, words: WordlistIterator<'domain>) -> impl Iterator<Item = String> + 'domain {
+    /// #     let (first, rest) = domain_components.all().split_first().expect("Empty domain.");
+    /// #     words.map(move |word| format!("{}-{}.{}", first, word, rest.join(".")))
+    /// # }
+    /// let manager = RipGenManager::new(vec!["api.example.com"].into_iter(), vec!["eu", "payments"].into_iter(), &|_| false)
+    ///     .expect("Failed to parse domains.");
+    ///
+    /// let affixes = manager.word_source(|word| word.len() <= 2);
+    /// let services = manager.word_source(|word| word.len() > 2);
+    ///
+    /// let names: Vec<String> = manager
+    ///     .transform_with_words(services.iter(), depth_permute_transform(1, 0, None))
+    ///     .chain_transform_with_words(affixes.iter(), dash_transform)
+    ///     .collect();
+    ///
+    /// assert!(names.contains(&"payments.api.example.com".to_string()));
+    /// assert!(names.contains(&"api-eu.example.com".to_string()));
+    /// assert!(!names.contains(&"eu.api.example.com".to_string()));
+    /// assert!(!names.contains(&"api-payments.example.com".to_string()));
+    /// ```
+    pub fn word_source(&self, filter: impl Fn(&&str) -> bool) -> WordSource<'domain> {
+        self.word_source_from(self.elements.iter().copied(), filter)
+    }
+
+    /// Returns the words that pass `filter` like [word_source](Self::word_source), but out of
+    /// `words` instead of the manager's words, e.g. a separate wordlist.
+    pub fn word_source_from(&self, words: impl Iterator<Item = &'domain str>, filter: impl Fn(&&str) -> bool) -> WordSource<'domain> {
+        WordSource { words: words.filter(filter).collect() }
     }
 }
@@ -0,0 +1,378 @@
+//! An n-gram model of subdomain labels that can generate plausible labels no wordlist contains.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use fxhash::{FxHashMap, FxHashSet};
+use crate::{DomainComponents, WordlistIterator};
+use crate::words::split_segments;
+
+const START: &str = "^";
+const END: &str = "$";
+
+/// The unit an [NgramModel] is trained on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Granularity {
+    /// Labels are sequences of characters.
+    Char,
+    /// Labels are sequences of tokens, split on `-` and runs of digits.
+    Token
+}
+
+/// Configures how an [NgramModel] is trained and how labels are generated from it.
+#[derive(Clone, Debug)]
+pub struct NgramOptions {
+    /// The number of units in each n-gram, including the one being predicted.
+    pub order: usize,
+    pub granularity: Granularity,
+    /// Labels less likely than this are never generated.
+    pub min_probability: f64,
+    /// The maximum number of labels to generate.
+    pub max_labels: usize,
+    /// The maximum number of units in a generated label.
+    pub max_len: usize,
+    /// When set, labels are randomly sampled with this seed instead of enumerated from most to least likely.
+    pub seed: Option<u64>
+}
+
+impl Default for NgramOptions {
+    fn default() -> Self {
+        Self {
+            order: 3,
+            granularity: Granularity::Char,
+            min_probability: 0.0001,
+            max_labels: 1000,
+            max_len: 24,
+            seed: None
+        }
+    }
+}
+
+/// An n-gram model of subdomain labels.
+///
+/// ```
+/// # use ripgen_lib::RipGenManager;
+/// # use ripgen_lib::markov::{Granularity, NgramModel, NgramOptions};
+/// let domains = vec!["api-dev.example.com", "api-prod.example.com", "web-prod.example.com", "web-stage.example.com"];
+/// let manager = RipGenManager::new(domains.into_iter(), std::iter::empty(), &|_| true)
+///     .expect("Failed to parse domains.");
+///
+/// let mut model = NgramModel::new(NgramOptions {
+///     order: 2,
+///     granularity: Granularity::Token,
+///     ..NgramOptions::default()
+/// });
+/// model.train_domains(manager.domains());
+///
+/// let mut labels = model.labels();
+/// labels.sort();
+///
+/// assert_eq!(labels, vec!["api-stage", "web-dev"]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct NgramModel {
+    options: NgramOptions,
+    counts: FxHashMap<Vec<String>, FxHashMap<String, usize>>,
+    observed: FxHashSet<String>
+}
+
+impl NgramModel {
+    /// Creates an untrained model.
+    pub fn new(options: NgramOptions) -> Self {
+        Self {
+            options,
+            counts: FxHashMap::default(),
+            observed: FxHashSet::default()
+        }
+    }
+
+    /// Trains the model on every subdomain label of the domains.
+    pub fn train_domains<'manager, 'domain: 'manager>(
+        &mut self,
+        domains: impl Iterator<Item = &'manager DomainComponents<'domain>>
+    ) {
+        for domain in domains {
+            for label in domain.subdomains() {
+                self.train_label(label);
+            }
+        }
+    }
+
+    /// Trains the model on a corpus of names, one per line. Every label of a name is used.
+    pub fn train_corpus<'a>(&mut self, lines: impl Iterator<Item = &'a str>) {
+        for line in lines {
+            for label in line.trim().split('.') {
+                self.train_label(label);
+            }
+        }
+    }
+
+    /// Trains the model on a single label.
+    pub fn train_label(&mut self, label: &str) {
+        let label = label.trim().to_lowercase();
+
+        if label.is_empty() || !self.observed.insert(label.clone()) {
+            return;
+        }
+
+        let context_len = self.options.order.max(1) - 1;
+        let mut units: Vec<String> = vec![START.to_string(); context_len];
+        units.extend(self.split_units(&label));
+        units.push(END.to_string());
+
+        for idx in context_len .. units.len() {
+            let context = units[idx - context_len .. idx].to_vec();
+
+            *self.counts
+                .entry(context)
+                .or_default()
+                .entry(units[idx].clone())
+                .or_default() += 1;
+        }
+    }
+
+    /// Generates new labels that were not trained on. Unless a seed is set, labels are returned
+    /// from most to least likely.
+    pub fn labels(&self) -> Vec<String> {
+        match self.options.seed {
+            Some(seed) => self.sample(seed),
+            None => self.enumerate()
+        }
+    }
+
+    /// Returns a transform that places every generated label directly under the root of each of
+    /// `domains`. Only the first of `domains` under a root produces its labels, so every root gets
+    /// them once without the transform having to remember which domains it has seen.
+    ///
+    /// Labels are generated once, when the transform is created.
+    ///
+    /// ```
+    /// # use ripgen_lib::RipGenManager;
+    /// # use ripgen_lib::markov::{Granularity, NgramModel, NgramOptions};
+    /// let domains = vec!["api-dev.example.com", "api-prod.example.com", "web-prod.example.com", "web-stage.example.com"];
+    /// let manager = RipGenManager::new(domains.into_iter(), std::iter::empty(), &|_| true)
+    ///     .expect("Failed to parse domains.");
+    ///
+    /// let mut model = NgramModel::new(NgramOptions {
+    ///     order: 2,
+    ///     granularity: Granularity::Token,
+    ///     ..NgramOptions::default()
+    /// });
+    /// model.train_domains(manager.domains());
+    ///
+    /// let mut names: Vec<String> = manager
+    ///     .transform(model.transform(manager.domains()))
+    ///     .collect();
+    /// names.sort();
+    ///
+    /// assert_eq!(names, vec!["api-stage.example.com", "web-dev.example.com"]);
+    /// ```
+    pub fn transform<'manager, 'domain: 'manager>(
+        &self,
+        domains: impl Iterator<Item = &'manager DomainComponents<'domain>>
+    ) -> impl Fn(&DomainComponents, WordlistIterator) -> std::vec::IntoIter<String> + Send + Sync {
+        let labels = self.labels();
+        let mut first_domains: FxHashMap<String, String> = FxHashMap::default();
+
+        for domain in domains {
+            if !first_domains.contains_key(domain.root()) {
+                first_domains.insert(domain.root().to_string(), domain.all().join("."));
+            }
+        }
+
+        move |domain_components: &DomainComponents, _: WordlistIterator| -> std::vec::IntoIter<String> {
+            let root = domain_components.root();
+            let is_first = first_domains
+                .get(root)
+                .is_some_and(|first| *first == domain_components.all().join("."));
+
+            if !is_first {
+                return Vec::new().into_iter();
+            }
+
+            labels
+                .iter()
+                .map(|label| format!("{label}.{root}"))
+                .collect::<Vec<String>>()
+                .into_iter()
+        }
+    }
+
+    fn split_units(&self, label: &str) -> Vec<String> {
+        match self.options.granularity {
+            Granularity::Char => label.chars().map(String::from).collect(),
+            Granularity::Token => split_segments(label).into_iter().map(String::from).collect()
+        }
+    }
+
+    /// Returns the possible next units after `units` along with their probabilities.
+    fn next_units(&self, units: &[String]) -> Vec<(&str, f64)> {
+        let context_len = self.options.order.max(1) - 1;
+        let context = &units[units.len() - context_len ..];
+
+        let next = match self.counts.get(context) {
+            Some(next) => next,
+            None => return Vec::new()
+        };
+
+        let total: usize = next.values().sum();
+
+        next.iter()
+            .map(|(unit, count)| (unit.as_str(), *count as f64 / total as f64))
+            .collect()
+    }
+
+    /// Turns a finished sequence of units into a label if it's new.
+    fn finish(&self, units: &[String]) -> Option<String> {
+        let context_len = self.options.order.max(1) - 1;
+        let label: String = units[context_len .. units.len() - 1].concat();
+
+        (!label.is_empty() && !self.observed.contains(&label)).then_some(label)
+    }
+
+    /// Walks the model from the most to least likely labels.
+    fn enumerate(&self) -> Vec<String> {
+        let context_len = self.options.order.max(1) - 1;
+        let mut results = Vec::new();
+        let mut queue = BinaryHeap::new();
+
+        queue.push(Candidate {
+            probability: 1.0,
+            units: vec![START.to_string(); context_len]
+        });
+
+        while let Some(candidate) = queue.pop() {
+            if results.len() >= self.options.max_labels {
+                break;
+            }
+
+            if candidate.units.last().map(String::as_str) == Some(END) {
+                results.extend(self.finish(&candidate.units));
+                continue;
+            }
+
+            if candidate.units.len() - context_len >= self.options.max_len {
+                continue;
+            }
+
+            for (unit, probability) in self.next_units(&candidate.units) {
+                let probability = candidate.probability * probability;
+
+                if probability < self.options.min_probability {
+                    continue;
+                }
+
+                let mut units = candidate.units.clone();
+                units.push(unit.to_string());
+
+                queue.push(Candidate { probability, units });
+            }
+        }
+
+        results
+    }
+
+    /// Randomly walks the model, deterministically for a given seed.
+    fn sample(&self, seed: u64) -> Vec<String> {
+        let context_len = self.options.order.max(1) - 1;
+        let mut rng = SplitMix64(seed);
+        let mut seen: FxHashSet<String> = FxHashSet::default();
+        let mut results = Vec::new();
+
+        // give up eventually if the model can't produce enough distinct labels
+        for _ in 0 .. self.options.max_labels.saturating_mul(10) {
+            if results.len() >= self.options.max_labels {
+                break;
+            }
+
+            let mut units = vec![START.to_string(); context_len];
+            let mut probability = 1.0;
+
+            while units.len() - context_len <= self.options.max_len {
+                let mut next = self.next_units(&units);
+
+                if next.is_empty() {
+                    break;
+                }
+
+                // hash map order isn't meaningful, so sort to keep sampling deterministic
+                next.sort_by(|left, right| left.0.cmp(right.0));
+
+                let mut roll = rng.next_f64();
+                let (unit, unit_probability) = *next
+                    .iter()
+                    .find(|(_, unit_probability)| {
+                        roll -= unit_probability;
+                        roll < 0.0
+                    })
+                    .unwrap_or(&next[next.len() - 1]);
+
+                probability *= unit_probability;
+                units.push(unit.to_string());
+
+                if unit == END {
+                    break;
+                }
+            }
+
+            if units.last().map(String::as_str) != Some(END) || probability < self.options.min_probability {
+                continue;
+            }
+
+            if let Some(label) = self.finish(&units) {
+                if seen.insert(label.clone()) {
+                    results.push(label);
+                }
+            }
+        }
+
+        results
+    }
+}
+
+/// A partially generated label, ordered by its probability.
+struct Candidate {
+    probability: f64,
+    units: Vec<String>
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // ties are broken on the units so enumeration is deterministic
+        self.probability
+            .total_cmp(&other.probability)
+            .then_with(|| other.units.cmp(&self.units))
+    }
+}
+
+/// A small, seedable PRNG so generation is reproducible without pulling in a dependency.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+
+        z ^ (z >> 31)
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
@@ -0,0 +1,117 @@
+use std::sync::Arc;
+use self_cell::self_cell;
+use crate::{RipGenError, RipGenManager};
+
+/// The strings every [OwnedRipGenManager] borrows from.
+struct Strings {
+    domains: Vec<String>,
+    words: Vec<String>
+}
+
+type BorrowedManager<'strings> = RipGenManager<'strings>;
+
+self_cell!(
+    struct Arena {
+        owner: Strings,
+
+        #[covariant]
+        dependent: BorrowedManager,
+    }
+);
+
+/// A [RipGenManager] that owns its domains and words, so it is `Send + Sync + 'static` and can be
+/// kept in long-lived structs or shared between threads. Clones share the same strings.
+///
+/// ```
+/// # use ripgen_lib::{OwnedRipGenManager, RipGenIterator};
+/// # use ripgen_lib::variants::{label_deletion_transform, label_reorder_transform};
+/// let manager = OwnedRipGenManager::new(
+///     vec!["a.b.example.com".to_string()].into_iter(),
+///     std::iter::empty::<String>(),
+///     &|_| true
+/// ).expect("Failed to parse domains.");
+/// # fn assert_shareable<T: Send + Sync + 'static>(_: &T) {}
+/// # assert_shareable(&manager);
+///
+/// let chain = manager.chain(|manager| Box::new(
+///     manager
+///         .transform(label_deletion_transform)
+///         .chain_transform(label_reorder_transform)
+/// ));
+///
+/// let mut names = std::thread::spawn(move || chain.collect::<Vec<String>>())
+///     .join()
+///     .expect("Worker panicked.");
+/// names.sort();
+///
+/// assert_eq!(names, vec!["a.example.com", "b.a.example.com", "b.example.com"]);
+/// ```
+#[derive(Clone)]
+pub struct OwnedRipGenManager {
+    arena: Arc<Arena>
+}
+
+impl OwnedRipGenManager {
+    /// Creates a new `OwnedRipGenManager`, taking ownership of the domains and words.
+    ///
+    /// This can fail if any of the `domains` are unable to be parsed.
+    pub fn new<D: Into<String>, W: Into<String>>(
+        domains: impl Iterator<Item = D>,
+        words: impl Iterator<Item = W>,
+        word_filter: &impl Fn(&&str) -> bool
+    ) -> Result<Self, RipGenError> {
+        let strings = Strings {
+            domains: domains.map(Into::into).collect(),
+            words: words.map(Into::into).collect()
+        };
+
+        let arena = Arena::try_new(strings, |strings| RipGenManager::new(
+            strings.domains.iter().map(String::as_str),
+            strings.words.iter().map(String::as_str),
+            word_filter
+        ))?;
+
+        Ok(Self { arena: Arc::new(arena) })
+    }
+
+    /// Returns the manager borrowing from the owned strings.
+    pub fn manager(&self) -> &RipGenManager<'_> {
+        self.arena.borrow_dependent()
+    }
+
+    /// Builds a chain of transforms that keeps the strings alive on its own, so it can be moved to
+    /// another thread or held across `.await` points.
+    pub fn chain<F>(&self, build: F) -> OwnedRipGenChain
+        where
+            F: for<'manager> FnOnce(&'manager RipGenManager<'manager>) -> BoxedChain<'manager>
+    {
+        OwnedRipGenChain {
+            inner: ChainCell::new(self.arena.clone(), |arena| build(arena.borrow_dependent()))
+        }
+    }
+}
+
+/// A chain built over a [RipGenManager], boxed so its type doesn't depend on the transforms.
+pub type BoxedChain<'manager> = Box<dyn Iterator<Item = String> + Send + 'manager>;
+
+self_cell!(
+    struct ChainCell {
+        owner: Arc<Arena>,
+
+        #[covariant]
+        dependent: BoxedChain,
+    }
+);
+
+/// A chain of transforms created by [OwnedRipGenManager::chain].
+pub struct OwnedRipGenChain {
+    inner: ChainCell
+}
+
+impl Iterator for OwnedRipGenChain {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.with_dependent_mut(|_, chain| chain.next())
+    }
+}
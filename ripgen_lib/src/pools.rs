@@ -0,0 +1,97 @@
+use std::slice::Iter as SliceIter;
+use std::sync::Arc;
+use fxhash::FxHashMap;
+use crate::{DomainComponents, WordsFor};
+use crate::words::{WordScope, WordSet};
+
+/// Which pool a domain's words belong to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum PoolKey<'domain> {
+    Root(&'domain str),
+    Group(usize)
+}
+
+/// Separate word pools for each root or input group, see [WordScope]. Every pool holds the words
+/// extracted from its own domains plus every wordlist entry.
+#[derive(Clone, Debug)]
+pub(crate) struct WordPools<'domain> {
+    scope: WordScope,
+    keys: FxHashMap<PoolKey<'domain>, usize>,
+    pools: Vec<WordSet<'domain>>,
+    wordlist: WordSet<'domain>,
+    /// Always empty, for domains without a pool.
+    empty: WordSet<'domain>
+}
+
+impl<'domain> WordPools<'domain> {
+    pub(crate) fn new(scope: WordScope, wordlist: WordSet<'domain>) -> Self {
+        Self {
+            scope,
+            keys: FxHashMap::default(),
+            pools: Vec::new(),
+            wordlist,
+            empty: WordSet::default()
+        }
+    }
+
+    /// Returns the words of the domain's pool.
+    pub(crate) fn words_for(&self, domain: &DomainComponents<'domain>) -> &WordSet<'domain> {
+        match self.keys.get(&self.key(domain)) {
+            Some(pool) => &self.pools[*pool],
+            None => &self.empty
+        }
+    }
+
+    /// Returns a lookup of [words_for](Self::words_for) for transforms.
+    pub(crate) fn lookup<'manager>(&'manager self) -> WordsFor<'manager, 'domain, SliceIter<'manager, &'domain str>> {
+        Arc::new(move |domain| self.words_for(domain).iter())
+    }
+
+    /// Adds words extracted from `domain` to its pool, creating the pool if needed.
+    pub(crate) fn add_domain_words(&mut self, domain: &DomainComponents<'domain>, words: impl Iterator<Item = &'domain str>) {
+        let key = self.key(domain);
+
+        let pool = match self.keys.get(&key) {
+            Some(pool) => *pool,
+            None => {
+                self.keys.insert(key, self.pools.len());
+                self.pools.push(self.wordlist.clone());
+
+                self.pools.len() - 1
+            }
+        };
+
+        self.pools[pool].extend(words);
+    }
+
+    /// Adds wordlist entries, which belong to every pool.
+    pub(crate) fn add_wordlist(&mut self, words: &[&'domain str]) {
+        self.wordlist.extend(words.iter().copied());
+
+        for pool in self.pools.iter_mut() {
+            pool.extend(words.iter().copied());
+        }
+    }
+
+    /// Keeps only the words of each pool for which `keep` returns true, given the word's position
+    /// within its pool.
+    pub(crate) fn retain_positions(&mut self, keep: impl Fn(usize) -> bool) {
+        for pool in self.pools.iter_mut() {
+            pool.retain_positions(&keep);
+        }
+    }
+
+    /// Orders the words of each pool by their weight, see [WordSet::order_by].
+    pub(crate) fn order_by(&mut self, weights: &FxHashMap<&'domain str, f64>) {
+        for pool in self.pools.iter_mut() {
+            pool.order_by(weights);
+        }
+    }
+
+    fn key(&self, domain: &DomainComponents<'domain>) -> PoolKey<'domain> {
+        match self.scope {
+            WordScope::Group => PoolKey::Group(domain.group),
+            WordScope::Root | WordScope::Global => PoolKey::Root(domain.root())
+        }
+    }
+}
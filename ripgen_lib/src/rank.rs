@@ -0,0 +1,482 @@
+//! Scores generated names by how likely they are to exist so output can be ordered best-first.
+//!
+//! A name's score is the natural log of its likelihood and combines:
+//!
+//! * how frequently each of its tokens appeared in the input domains and wordlist,
+//! * the [weight](crate::wordlist::WordEntry::weight) of its tokens in a weighted wordlist,
+//! * how common its structure (the shape of its labels) is among the input domains, and
+//! * the prior of the transform that produced it, see [TransformPriors].
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::sync::Arc;
+use fxhash::FxHashMap;
+use crate::{DomainComponents, RipGenManager};
+use crate::checkpoint::{Checkpoint, Resumable};
+use crate::feedback::FeedbackModel;
+use crate::wordlist::Wordlist;
+use crate::words::{split_segments, is_token};
+
+/// Scores names against the words and structure of a manager's input domains.
+#[derive(Clone, Debug, Default)]
+pub struct Scorer {
+    token_counts: FxHashMap<String, usize>,
+    total_tokens: usize,
+    shape_counts: FxHashMap<String, usize>,
+    total_shapes: usize,
+    word_weights: FxHashMap<String, f64>,
+    /// The highest and the average word weight, or 0 without weights.
+    max_weight: f64,
+    mean_weight: f64
+}
+
+impl Scorer {
+    /// Learns token frequencies and name structures from the manager's domains and words.
+    pub fn new(manager: &RipGenManager) -> Self {
+        let mut scorer = Self::default();
+
+        for domain in manager.domains() {
+            scorer.observe(domain);
+        }
+
+        for word in manager.words() {
+            scorer.observe_token(word);
+        }
+
+        scorer
+    }
+
+    /// Adds a domain's tokens and structure to the scorer.
+    pub fn observe(&mut self, domain: &DomainComponents) {
+        for label in domain.subdomains() {
+            for token in split_segments(label).into_iter().filter(|segment| is_token(segment)) {
+                self.observe_token(token);
+            }
+        }
+
+        *self.shape_counts.entry(shape(domain.subdomains())).or_default() += 1;
+        self.total_shapes += 1;
+    }
+
+    /// Adds a single token to the scorer.
+    pub fn observe_token(&mut self, token: &str) {
+        self.observe_token_count(token, 1);
+    }
+
+    /// Counts every word that produced confirmed names once more per confirmed name.
+    pub fn apply_feedback(&mut self, feedback: &FeedbackModel) {
+        for (word, hits) in feedback.words() {
+            self.observe_token_count(word, hits as usize);
+        }
+    }
+
+    /// Weighs every token by the weight of the same word in `wordlist`, relative to the highest
+    /// weight. Tokens that aren't in the wordlist get the average weight, so that only words
+    /// weighted below average score lower than unknown tokens.
+    ///
+    /// ```
+    /// # use ripgen_lib::RipGenManager;
+    /// # use ripgen_lib::rank::Scorer;
+    /// # use ripgen_lib::wordlist::Wordlist;
+    /// let wordlist = Wordlist::parse("admin 0.9\nlegacy 0.1").expect("Failed to parse wordlist.");
+    /// let manager = RipGenManager::new(vec!["www.example.com"].into_iter(), wordlist.words(), &|_| false)
+    ///     .expect("Failed to parse domains.");
+    ///
+    /// let mut scorer = Scorer::new(&manager);
+    /// scorer.apply_weights(&wordlist);
+    ///
+    /// assert!(scorer.score("admin.example.com") > scorer.score("legacy.example.com"));
+    /// assert!(scorer.score("admin.example.com") > scorer.score("mail.example.com"));
+    /// assert!(scorer.score("mail.example.com") > scorer.score("legacy.example.com"));
+    /// ```
+    pub fn apply_weights(&mut self, wordlist: &Wordlist) {
+        for entry in wordlist.entries() {
+            let weight = self.word_weights.entry(entry.word.to_lowercase()).or_insert(entry.weight);
+            *weight = weight.max(entry.weight);
+        }
+
+        if !self.word_weights.is_empty() {
+            self.max_weight = self.word_weights.values().copied().fold(0.0, f64::max);
+            self.mean_weight = self.word_weights.values().sum::<f64>() / self.word_weights.len() as f64;
+        }
+    }
+
+    /// Returns the weight of a lowercase token relative to the highest weight, or 1 without weights.
+    fn weight(&self, token: &str) -> f64 {
+        if self.max_weight <= 0.0 {
+            return 1.0;
+        }
+
+        self.word_weights.get(token).copied().unwrap_or(self.mean_weight) / self.max_weight
+    }
+
+    fn observe_token_count(&mut self, token: &str, count: usize) {
+        *self.token_counts.entry(token.to_lowercase()).or_default() += count;
+        self.total_tokens += count;
+    }
+
+    /// Returns the log-likelihood of a name. Names that can't be parsed score negative infinity.
+    ///
+    /// ```
+    /// # use ripgen_lib::RipGenManager;
+    /// # use ripgen_lib::rank::Scorer;
+    /// let domains = vec!["api-dev.example.com", "api-prod.example.com", "www.example.com"];
+    /// let manager = RipGenManager::new(domains.into_iter(), std::iter::empty(), &|_| false)
+    ///     .expect("Failed to parse domains.");
+    ///
+    /// let scorer = Scorer::new(&manager);
+    ///
+    /// assert!(scorer.score("api-stage.example.com") > scorer.score("qwerty.zxcv.example.com"));
+    /// ```
+    pub fn score(&self, name: &str) -> f64 {
+        let domain = match DomainComponents::try_from(name) {
+            Ok(domain) => domain,
+            Err(_) => return f64::NEG_INFINITY
+        };
+
+        let vocabulary = (self.token_counts.len() + 1) as f64;
+
+        let token_scores: Vec<f64> = domain
+            .subdomains()
+            .iter()
+            .flat_map(|label| split_segments(label))
+            .filter(|segment| is_token(segment))
+            .map(|token| {
+                let token = token.to_lowercase();
+                let count = self.token_counts.get(&token).copied().unwrap_or(0);
+                let weight = self.weight(&token);
+
+                // add-one smoothing so unseen tokens are unlikely rather than impossible
+                ((count + 1) as f64 / (self.total_tokens as f64 + vocabulary)).ln() + weight.ln()
+            })
+            .collect();
+
+        // averaged so that names aren't penalized for every extra token
+        let token_score = match token_scores.is_empty() {
+            true => 0.0,
+            false => token_scores.iter().sum::<f64>() / token_scores.len() as f64
+        };
+
+        let shape_count = self.shape_counts.get(&shape(domain.subdomains())).copied().unwrap_or(0);
+        let shape_score = ((shape_count + 1) as f64 / (self.total_shapes + 1) as f64).ln();
+
+        token_score + shape_score
+    }
+}
+
+/// Describes the structure of the subdomains, e.g. `api-dev2.www` -> `w-wn.w`.
+fn shape(labels: &[&str]) -> String {
+    labels
+        .iter()
+        .map(|label| {
+            split_segments(label)
+                .into_iter()
+                .map(|segment| match segment {
+                    segment if segment.starts_with('-') => '-',
+                    segment if is_token(segment) => 'w',
+                    _ => 'n'
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join(".")
+}
+
+/// Records the name and prior of every transform in a chain so each name taken from the chain can
+/// be [attributed](Self::attribute) to the transform that produced it.
+///
+/// Every transform of the chain must be wrapped with [with_prior](Self::with_prior), in the order
+/// they're chained. When created [with feedback](Self::with_feedback), priors are adjusted by how
+/// productive each transform has been and, if pruning, transforms without any confirmed names are
+/// skipped.
+///
+/// ```
+/// # use ripgen_lib::{RipGenIterator, RipGenManager};
+/// # use ripgen_lib::rank::TransformPriors;
+/// # use ripgen_lib::variants::{label_deletion_transform, label_reorder_transform};
+/// let manager = RipGenManager::new(vec!["a.b.example.com"].into_iter(), std::iter::empty(), &|_| true)
+///     .expect("Failed to parse domains.");
+///
+/// let mut priors = TransformPriors::default();
+/// let chain = manager
+///     .transform(priors.with_prior("label-deletion", 0.5, label_deletion_transform))
+///     .chain_transform(priors.with_prior("label-reorder", 0.3, label_reorder_transform));
+///
+/// let mut names: Vec<(String, &str)> = priors.attribute(chain).collect();
+/// names.sort();
+///
+/// assert_eq!(names, vec![
+///     ("a.example.com".to_string(), "label-deletion"),
+///     ("b.a.example.com".to_string(), "label-reorder"),
+///     ("b.example.com".to_string(), "label-deletion")
+/// ]);
+/// assert_eq!(priors.prior("label-reorder"), 0.3);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TransformPriors {
+    transforms: Vec<(&'static str, f64)>,
+    feedback: Option<Arc<FeedbackModel>>,
+    prune: bool
+}
+
+impl TransformPriors {
+    /// Creates priors that are adjusted by a [FeedbackModel].
+    pub fn with_feedback(feedback: FeedbackModel, prune: bool) -> Self {
+        Self {
+            feedback: Some(Arc::new(feedback)),
+            prune,
+            ..Self::default()
+        }
+    }
+
+    /// Records `name` with `prior`, a probability in `(0, 1]`, as the next transform of the chain
+    /// and returns `transform`, which is skipped if it's pruned.
+    pub fn with_prior<A, W, F, O>(
+        &mut self,
+        name: &'static str,
+        prior: f64,
+        transform: F
+    ) -> impl Fn(A, W) -> std::iter::Flatten<std::option::IntoIter<O>>
+        where
+            F: Fn(A, W) -> O,
+            O: Iterator<Item = String>
+    {
+        let (prior, enabled) = match self.feedback {
+            Some(ref feedback) => (
+                feedback.transform_prior(name, prior),
+                !self.prune || feedback.is_productive(name)
+            ),
+            None => (prior, true)
+        };
+
+        self.transforms.push((name, prior));
+
+        move |domain_components, words| {
+            enabled
+                .then(|| transform(domain_components, words))
+                .into_iter()
+                .flatten()
+        }
+    }
+
+    /// Pairs every name taken from `chain` with the name of the transform that produced it.
+    pub fn attribute<I>(&self, chain: I) -> Attributed<I>
+        where
+            I: Iterator<Item = String> + Resumable
+    {
+        debug_assert_eq!(self.transforms.len(), I::TRANSFORMS, "Every transform of the chain needs a prior.");
+
+        Attributed {
+            chain,
+            transforms: self.transforms.iter().map(|(name, _)| *name).collect()
+        }
+    }
+
+    /// Returns the prior of a recorded transform, or 1 for any other transform.
+    pub fn prior(&self, transform: &str) -> f64 {
+        self.transforms
+            .iter()
+            .find(|(name, _)| *name == transform)
+            .map_or(1.0, |(_, prior)| *prior)
+    }
+
+    /// Returns the log of a transform's prior so it can be added to a [Scorer] score.
+    pub fn score(&self, transform: &str) -> f64 {
+        self.prior(transform).ln()
+    }
+}
+
+/// A chain whose names are paired with the transform that produced them, see
+/// [TransformPriors::attribute].
+pub struct Attributed<I> {
+    chain: I,
+    transforms: Vec<&'static str>
+}
+
+impl<I> Iterator for Attributed<I>
+    where
+        I: Iterator<Item = String> + Resumable
+{
+    type Item = (String, &'static str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let name = self.chain.next()?;
+        let transform = self.transforms
+            .get(self.chain.checkpoint().transform)
+            .copied()
+            .unwrap_or_default();
+
+        Some((name, transform))
+    }
+}
+
+impl<I> Resumable for Attributed<I>
+    where
+        I: Iterator<Item = String> + Resumable
+{
+    const TRANSFORMS: usize = I::TRANSFORMS;
+
+    fn checkpoint(&self) -> Checkpoint {
+        self.chain.checkpoint()
+    }
+
+    fn resume(&mut self, checkpoint: Checkpoint) {
+        self.chain.resume(checkpoint)
+    }
+}
+
+/// Keeps the `capacity` best scoring unique names seen so far, each along with a value such as
+/// the transform that produced it.
+///
+/// ```
+/// # use ripgen_lib::rank::BoundedRanking;
+/// let mut ranking = BoundedRanking::new(2);
+/// ranking.push_with("api.example.com".to_string(), "dash", -3.0);
+/// ranking.push_with("api.example.com".to_string(), "permute", -1.0);
+/// ranking.push_with("api.example.com".to_string(), "numbers", -2.0);
+/// ranking.push_with("www.example.com".to_string(), "dash", -2.0);
+/// ranking.push_with("dev.example.com".to_string(), "dash", -4.0);
+/// ranking.push_with("www.example.com".to_string(), "numbers", -1.5);
+/// ranking.push_with("dev.example.com".to_string(), "permute", -1.8);
+///
+/// assert_eq!(ranking.into_sorted_entries(), vec![
+///     ("api.example.com".to_string(), "permute"),
+///     ("www.example.com".to_string(), "numbers")
+/// ]);
+/// ```
+pub struct BoundedRanking<T = ()> {
+    capacity: usize,
+    heap: BinaryHeap<Reverse<Ranked<T>>>,
+    /// The score and entry id each kept name is kept with. Heap entries of a name with another id
+    /// were replaced by a better score, and are dropped once they surface.
+    members: FxHashMap<String, (f64, u64)>,
+    next_id: u64
+}
+
+impl<T> BoundedRanking<T> {
+    /// Creates an empty ranking that keeps at most `capacity` names.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            heap: BinaryHeap::with_capacity(capacity.min(1024 * 1024)),
+            members: FxHashMap::default(),
+            next_id: 0
+        }
+    }
+
+    /// Offers a name and its value to the ranking. It's kept if it's one of the best `capacity`
+    /// names so far. A name that is offered again only replaces its earlier value if it scores
+    /// higher this time.
+    pub fn push_with(&mut self, name: String, value: T, score: f64) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        match self.members.get(&name) {
+            Some((kept, _)) if kept.total_cmp(&score) != Ordering::Less => return,
+            // the name's earlier entry goes stale, so nothing needs to be evicted
+            Some(_) => {},
+            None if self.members.len() >= self.capacity => {
+                self.drop_stale();
+
+                match self.heap.peek() {
+                    Some(Reverse(worst)) if worst.score.total_cmp(&score) == Ordering::Less => {},
+                    _ => return
+                }
+
+                if let Some(Reverse(evicted)) = self.heap.pop() {
+                    self.members.remove(&evicted.name);
+                }
+            },
+            None => {}
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.members.insert(name.clone(), (score, id));
+        self.heap.push(Reverse(Ranked { score, name, value, id }));
+
+        // stale entries that never surface would otherwise grow the heap without bound
+        if self.heap.len() > self.capacity.saturating_mul(2) {
+            let members = &self.members;
+            self.heap.retain(|Reverse(ranked)| ranked.is_kept(members));
+        }
+    }
+
+    /// Pops stale entries off the top of the heap, so it peeks at the worst kept name.
+    fn drop_stale(&mut self) {
+        while let Some(Reverse(ranked)) = self.heap.peek() {
+            if ranked.is_kept(&self.members) {
+                break;
+            }
+
+            self.heap.pop();
+        }
+    }
+
+    /// Returns the kept names and their values from best to worst.
+    pub fn into_sorted_entries(self) -> Vec<(String, T)> {
+        let members = self.members;
+
+        // `Reverse` flips the order, so ascending here is best-first
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .filter(|Reverse(ranked)| ranked.is_kept(&members))
+            .map(|Reverse(ranked)| (ranked.name, ranked.value))
+            .collect()
+    }
+
+    /// Returns the kept names from best to worst.
+    pub fn into_sorted_vec(self) -> Vec<String> {
+        self.into_sorted_entries()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect()
+    }
+}
+
+impl BoundedRanking {
+    /// Offers a name to the ranking. It's kept if it's one of the best `capacity` names so far.
+    pub fn push(&mut self, name: String, score: f64) {
+        self.push_with(name, (), score);
+    }
+}
+
+struct Ranked<T> {
+    score: f64,
+    name: String,
+    value: T,
+    id: u64
+}
+
+impl<T> Ranked<T> {
+    /// Returns whether this is the entry its name is currently kept with.
+    fn is_kept(&self, members: &FxHashMap<String, (f64, u64)>) -> bool {
+        members.get(&self.name).is_some_and(|&(_, id)| id == self.id)
+    }
+}
+
+impl<T> PartialEq for Ranked<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Ranked<T> {}
+
+impl<T> PartialOrd for Ranked<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Ranked<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // ties prefer the alphabetically first name so rankings are deterministic
+        self.score
+            .total_cmp(&other.score)
+            .then_with(|| other.name.cmp(&self.name))
+    }
+}
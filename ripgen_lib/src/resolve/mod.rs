@@ -0,0 +1,297 @@
+//! Resolves generated names asynchronously against a configurable set of nameservers so output
+//! can be limited to names that exist.
+
+mod wildcard;
+mod wire;
+
+pub use wildcard::WildcardDetector;
+
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::net::UdpSocket;
+use tokio::task::JoinSet;
+use tokio::time::{interval, timeout, MissedTickBehavior};
+use crate::RipGenError;
+use crate::resolve::wire::{ResponseCode, TYPE_A, TYPE_AAAA};
+
+const MAX_RESPONSE_LEN: usize = 4096;
+
+/// A record returned for a name.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Record {
+    A(Ipv4Addr),
+    Aaaa(Ipv6Addr),
+    Cname(String)
+}
+
+/// The outcome of resolving a single name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Resolution {
+    pub name: String,
+    /// The A, AAAA and CNAME records of the name. Empty if the name doesn't exist, has no such
+    /// records, or every attempt to resolve it failed.
+    pub records: Vec<Record>,
+    /// Whether the records match the answers of a wildcard in the parent zone. Only checked when
+    /// [filter_wildcards](ResolverConfig::filter_wildcards) is set.
+    pub wildcard: bool
+}
+
+impl Resolution {
+    /// Returns whether the name has any A, AAAA or CNAME records.
+    pub fn is_resolved(&self) -> bool {
+        !self.records.is_empty()
+    }
+
+    /// Returns whether the name resolved to something other than the wildcard answers of its parent zone.
+    pub fn is_genuine(&self) -> bool {
+        self.is_resolved() && !self.wildcard
+    }
+}
+
+/// Configures a [Resolver].
+#[derive(Clone, Debug)]
+pub struct ResolverConfig {
+    /// The nameservers queries are spread across. Retries go to the next nameserver.
+    pub nameservers: Vec<SocketAddr>,
+    /// The maximum number of names being resolved at once.
+    pub concurrency: usize,
+    /// The maximum number of names to start resolving per second.
+    pub rate_limit: Option<u32>,
+    /// How many more times a query is sent after a timeout or failure response.
+    pub retries: usize,
+    /// How long to wait for each response.
+    pub timeout: Duration,
+    /// Whether to check resolved names against the wildcard answers of their parent zone.
+    pub filter_wildcards: bool,
+    /// How many random labels are resolved under each parent zone to find its wildcard answers.
+    pub wildcard_probes: usize
+}
+
+impl Default for ResolverConfig {
+    fn default() -> Self {
+        Self {
+            nameservers: vec![
+                SocketAddr::from(([1, 1, 1, 1], 53)),
+                SocketAddr::from(([8, 8, 8, 8], 53)),
+                SocketAddr::from(([9, 9, 9, 9], 53))
+            ],
+            concurrency: 500,
+            rate_limit: None,
+            retries: 2,
+            timeout: Duration::from_secs(2),
+            filter_wildcards: true,
+            wildcard_probes: 3
+        }
+    }
+}
+
+/// An asynchronous UDP DNS client.
+///
+/// ```
+/// # use std::net::UdpSocket;
+/// # use ripgen_lib::resolve::{Resolver, ResolverConfig};
+/// # // a stub nameserver that answers `www.example.com` with 127.0.0.1 and everything else with NXDOMAIN
+/// # let stub = UdpSocket::bind("127.0.0.1:0").unwrap();
+/// # let stub_addr = stub.local_addr().unwrap();
+/// # std::thread::spawn(move || loop {
+/// #     let mut buf = [0u8; 512];
+/// #     let (len, peer) = stub.recv_from(&mut buf).unwrap();
+/// #     let mut response = buf[.. len].to_vec();
+/// #     let found = response.windows(3).any(|window| window == b"www") && response[len - 3] == 1;
+/// #     response[2] = 0x81;
+/// #     response[3] = if found { 0x80 } else { 0x83 };
+/// #     if found {
+/// #         response[7] = 1;
+/// #         response.extend([0xC0, 0x0C, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4, 127, 0, 0, 1]);
+/// #     }
+/// #     stub.send_to(&response, peer).unwrap();
+/// # });
+/// let resolver = Resolver::new(ResolverConfig {
+///     nameservers: vec![stub_addr],
+///     ..ResolverConfig::default()
+/// }).expect("Failed to create resolver.");
+///
+/// let names = vec!["www.example.com".to_string(), "missing.example.com".to_string()];
+/// let mut resolved = Vec::new();
+///
+/// resolver.resolve_blocking(names.into_iter(), |resolution| {
+///     if resolution.is_genuine() {
+///         resolved.push(resolution.name);
+///     }
+///
+///     true
+/// }).expect("Failed to resolve.");
+///
+/// assert_eq!(resolved, vec!["www.example.com"]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Resolver {
+    config: Arc<ResolverConfig>,
+    next_id: Arc<AtomicU16>,
+    wildcards: Option<Arc<WildcardDetector>>
+}
+
+impl Resolver {
+    pub fn new(config: ResolverConfig) -> Result<Self, RipGenError> {
+        if config.nameservers.is_empty() {
+            return Err(RipGenError::NoNameservers);
+        }
+
+        // query ids only need to differ between runs, not be unpredictable
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.subsec_nanos() as u16)
+            .unwrap_or_default();
+
+        let wildcards = config.filter_wildcards
+            .then(|| Arc::new(WildcardDetector::new(config.wildcard_probes)));
+
+        Ok(Self {
+            config: Arc::new(config),
+            next_id: Arc::new(AtomicU16::new(seed)),
+            wildcards
+        })
+    }
+
+    /// Resolves a single name, asking for A records and then AAAA records if there were none.
+    /// CNAME records are returned alongside whichever was asked for.
+    pub async fn lookup(&self, name: &str) -> Resolution {
+        let mut records = Vec::new();
+
+        for record_type in [TYPE_A, TYPE_AAAA] {
+            match self.query(name, record_type).await {
+                Some((ResponseCode::NoError, answers)) => records.extend(answers),
+                // the name doesn't exist, so there's no point asking for other record types
+                _ => break
+            }
+
+            if !records.is_empty() {
+                break;
+            }
+        }
+
+        Resolution { name: name.to_string(), records, wildcard: false }
+    }
+
+    /// Looks up a name and, if wildcards are being filtered, checks its records against the
+    /// wildcard answers of its parent zone.
+    pub async fn resolve(&self, name: &str) -> Resolution {
+        let mut resolution = self.lookup(name).await;
+
+        if let Some(ref wildcards) = self.wildcards {
+            resolution.wildcard = wildcards.is_wildcard(self, name, &resolution.records).await;
+        }
+
+        resolution
+    }
+
+    /// [Resolves](Self::resolve) every name, at most [concurrency](ResolverConfig::concurrency) at a time, and
+    /// passes each resolution to `on_resolution` as it completes. Stops early if `on_resolution`
+    /// returns false.
+    pub async fn resolve_all(
+        &self,
+        names: impl Iterator<Item = String>,
+        mut on_resolution: impl FnMut(Resolution) -> bool
+    ) -> Result<(), RipGenError> {
+        let mut tasks = JoinSet::new();
+        let concurrency = self.config.concurrency.max(1);
+
+        let mut limiter = self.config.rate_limit
+            .filter(|rate| *rate > 0)
+            .map(|rate| {
+                let mut limiter = interval(Duration::from_secs_f64(1.0 / rate as f64));
+                limiter.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                limiter
+            });
+
+        for name in names {
+            while tasks.len() >= concurrency {
+                if let Some(resolution) = tasks.join_next().await {
+                    let resolution = resolution.map_err(|e| RipGenError::ResolverTask(e.to_string()))?;
+
+                    if !on_resolution(resolution) {
+                        return Ok(());
+                    }
+                }
+            }
+
+            if let Some(ref mut limiter) = limiter {
+                limiter.tick().await;
+            }
+
+            let resolver = self.clone();
+            tasks.spawn(async move { resolver.resolve(&name).await });
+        }
+
+        while let Some(resolution) = tasks.join_next().await {
+            let resolution = resolution.map_err(|e| RipGenError::ResolverTask(e.to_string()))?;
+
+            if !on_resolution(resolution) {
+                return Ok(());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs [resolve_all](Self::resolve_all) to completion on a new single threaded runtime.
+    pub fn resolve_blocking(
+        &self,
+        names: impl Iterator<Item = String>,
+        on_resolution: impl FnMut(Resolution) -> bool
+    ) -> Result<(), RipGenError> {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?
+            .block_on(self.resolve_all(names, on_resolution))
+    }
+
+    /// Sends a query, retrying against the following nameservers. Returns `None` if no usable
+    /// response was received.
+    async fn query(&self, name: &str, record_type: u16) -> Option<(ResponseCode, Vec<Record>)> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let query = wire::build_query(id, name, record_type)?;
+        let nameservers = &self.config.nameservers;
+
+        for attempt in 0 ..= self.config.retries {
+            let nameserver = nameservers[(id as usize + attempt) % nameservers.len()];
+
+            let response = match timeout(self.config.timeout, exchange(nameserver, &query, id)).await {
+                Ok(Some(response)) => response,
+                _ => continue
+            };
+
+            match response.code {
+                ResponseCode::Failure => continue,
+                code => return Some((code, response.records))
+            }
+        }
+
+        None
+    }
+}
+
+/// Sends a query to a nameserver and waits for the response with the matching id.
+async fn exchange(nameserver: SocketAddr, query: &[u8], id: u16) -> Option<wire::Response> {
+    let local: SocketAddr = match nameserver {
+        SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into()
+    };
+
+    let socket = UdpSocket::bind(local).await.ok()?;
+    socket.connect(nameserver).await.ok()?;
+    socket.send(query).await.ok()?;
+
+    let mut buf = vec![0u8; MAX_RESPONSE_LEN];
+
+    loop {
+        let len = socket.recv(&mut buf).await.ok()?;
+
+        match wire::parse_response(&buf[.. len]) {
+            Some(response) if response.id == id => return Some(response),
+            _ => continue
+        }
+    }
+}
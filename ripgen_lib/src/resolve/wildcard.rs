@@ -0,0 +1,130 @@
+use std::collections::{BTreeSet, HashMap};
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::OnceCell;
+use crate::resolve::{Record, Resolver};
+
+const PROBE_LABEL_LEN: usize = 12;
+
+type WildcardAnswers = Option<Arc<BTreeSet<Record>>>;
+
+/// Detects wildcard zones by resolving random labels under each parent zone, and remembers the
+/// answers of each zone so it is only probed once.
+///
+/// A name is considered a wildcard match when every record it resolved to is among the answers
+/// of the random labels under its parent. Zones that rotate through more addresses than the probes
+/// saw may still let some wildcard matches through.
+///
+/// ```
+/// # use std::net::Ipv4Addr;
+/// # use ripgen_lib::resolve::{Record, Resolver, ResolverConfig, WildcardDetector};
+/// # use std::net::UdpSocket;
+/// # // a stub nameserver where everything under dev.example.com resolves to 10.0.0.1
+/// # let stub = UdpSocket::bind("127.0.0.1:0").unwrap();
+/// # let stub_addr = stub.local_addr().unwrap();
+/// # std::thread::spawn(move || loop {
+/// #     let mut buf = [0u8; 512];
+/// #     let (len, peer) = stub.recv_from(&mut buf).unwrap();
+/// #     let mut response = buf[.. len].to_vec();
+/// #     let found = response.windows(4).any(|window| window == b"\x03dev") && response[len - 3] == 1;
+/// #     response[2] = 0x81;
+/// #     response[3] = if found { 0x80 } else { 0x83 };
+/// #     if found {
+/// #         response[7] = 1;
+/// #         response.extend([0xC0, 0x0C, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4, 10, 0, 0, 1]);
+/// #     }
+/// #     stub.send_to(&response, peer).unwrap();
+/// # });
+/// let resolver = Resolver::new(ResolverConfig {
+///     nameservers: vec![stub_addr],
+///     ..ResolverConfig::default()
+/// }).expect("Failed to create resolver.");
+/// let detector = WildcardDetector::new(3);
+///
+/// let wildcard = vec![Record::A(Ipv4Addr::new(10, 0, 0, 1))];
+/// let genuine = vec![Record::A(Ipv4Addr::new(10, 0, 0, 2))];
+///
+/// let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+///
+/// runtime.block_on(async {
+///     assert!(detector.is_wildcard(&resolver, "api.dev.example.com", &wildcard).await);
+///     assert!(!detector.is_wildcard(&resolver, "api.dev.example.com", &genuine).await);
+///     assert!(!detector.is_wildcard(&resolver, "api.example.com", &wildcard).await);
+/// });
+/// ```
+#[derive(Debug)]
+pub struct WildcardDetector {
+    probes: usize,
+    zones: Mutex<HashMap<String, Arc<OnceCell<WildcardAnswers>>>>,
+    label_state: RandomState,
+    labels_made: AtomicU64
+}
+
+impl WildcardDetector {
+    /// Creates a detector that resolves `probes` random labels under each parent zone.
+    pub fn new(probes: usize) -> Self {
+        Self {
+            probes: probes.max(1),
+            zones: Mutex::new(HashMap::new()),
+            label_state: RandomState::new(),
+            labels_made: AtomicU64::new(0)
+        }
+    }
+
+    /// Returns whether `name` resolving to `records` can be explained by a wildcard in its parent zone.
+    pub async fn is_wildcard(&self, resolver: &Resolver, name: &str, records: &[Record]) -> bool {
+        let parent = match name.split_once('.') {
+            Some((_, parent)) if !parent.is_empty() => parent,
+            _ => return false
+        };
+
+        if records.is_empty() {
+            return false;
+        }
+
+        match self.wildcard_answers(resolver, parent).await {
+            Some(answers) => records.iter().all(|record| answers.contains(record)),
+            None => false
+        }
+    }
+
+    /// Returns every answer given for random labels under `parent`, or `None` if it isn't a wildcard zone.
+    pub async fn wildcard_answers(&self, resolver: &Resolver, parent: &str) -> WildcardAnswers {
+        // names under the same parent are often resolved at once, so they share a single probe
+        let zone = self.zones
+            .lock()
+            .expect("Wildcard cache was poisoned.")
+            .entry(parent.to_string())
+            .or_default()
+            .clone();
+
+        zone.get_or_init(|| self.probe(resolver, parent)).await.clone()
+    }
+
+    async fn probe(&self, resolver: &Resolver, parent: &str) -> WildcardAnswers {
+        let mut answers = BTreeSet::new();
+
+        for _ in 0 .. self.probes {
+            let name = format!("{}.{}", self.random_label(), parent);
+            answers.extend(resolver.lookup(&name).await.records);
+        }
+
+        (!answers.is_empty()).then(|| Arc::new(answers))
+    }
+
+    fn random_label(&self) -> String {
+        const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+        let mut seed = self.label_state.hash_one(self.labels_made.fetch_add(1, Ordering::Relaxed));
+
+        (0 .. PROBE_LABEL_LEN)
+            .map(|_| {
+                let c = ALPHABET[(seed % ALPHABET.len() as u64) as usize] as char;
+                seed /= ALPHABET.len() as u64;
+                c
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,158 @@
+//! Just enough of the DNS wire format (RFC 1035) to ask for and read A, AAAA and CNAME records.
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+use crate::resolve::Record;
+
+pub(crate) const TYPE_A: u16 = 1;
+pub(crate) const TYPE_CNAME: u16 = 5;
+pub(crate) const TYPE_AAAA: u16 = 28;
+
+const CLASS_IN: u16 = 1;
+const FLAG_RESPONSE: u16 = 0x8000;
+const FLAG_RECURSION_DESIRED: u16 = 0x0100;
+const HEADER_LEN: usize = 12;
+const MAX_POINTERS: usize = 16;
+
+/// The response code of a DNS response.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ResponseCode {
+    NoError,
+    NxDomain,
+    /// Any other response code, such as SERVFAIL or REFUSED, which is worth retrying elsewhere.
+    Failure
+}
+
+/// A parsed DNS response.
+pub(crate) struct Response {
+    pub(crate) id: u16,
+    pub(crate) code: ResponseCode,
+    pub(crate) records: Vec<Record>
+}
+
+/// Builds a recursive query for `name`. Returns `None` if `name` isn't a valid DNS name.
+pub(crate) fn build_query(id: u16, name: &str, record_type: u16) -> Option<Vec<u8>> {
+    let mut query = Vec::with_capacity(HEADER_LEN + name.len() + 6);
+
+    query.extend(id.to_be_bytes());
+    query.extend(FLAG_RECURSION_DESIRED.to_be_bytes());
+    // one question, no answer, authority or additional records
+    query.extend([0, 1, 0, 0, 0, 0, 0, 0]);
+
+    for label in name.trim_end_matches('.').split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return None;
+        }
+
+        query.push(label.len() as u8);
+        query.extend(label.as_bytes());
+    }
+
+    query.push(0);
+    query.extend(record_type.to_be_bytes());
+    query.extend(CLASS_IN.to_be_bytes());
+
+    Some(query)
+}
+
+/// Parses a response, keeping only the A, AAAA and CNAME answers. Returns `None` for anything
+/// that isn't a well formed response.
+pub(crate) fn parse_response(message: &[u8]) -> Option<Response> {
+    let id = read_u16(message, 0)?;
+    let flags = read_u16(message, 2)?;
+    let question_count = read_u16(message, 4)?;
+    let answer_count = read_u16(message, 6)?;
+
+    if flags & FLAG_RESPONSE == 0 {
+        return None;
+    }
+
+    let code = match flags & 0x000F {
+        0 => ResponseCode::NoError,
+        3 => ResponseCode::NxDomain,
+        _ => ResponseCode::Failure
+    };
+
+    let mut offset = HEADER_LEN;
+
+    for _ in 0 .. question_count {
+        offset = skip_name(message, offset)? + 4;
+    }
+
+    let mut records = Vec::with_capacity(answer_count as usize);
+
+    for _ in 0 .. answer_count {
+        offset = skip_name(message, offset)?;
+
+        let record_type = read_u16(message, offset)?;
+        let data_len = read_u16(message, offset + 8)? as usize;
+        let data_start = offset + 10;
+        let data = message.get(data_start .. data_start + data_len)?;
+
+        match record_type {
+            TYPE_A => {
+                let octets: [u8; 4] = data.try_into().ok()?;
+                records.push(Record::A(Ipv4Addr::from(octets)));
+            },
+            TYPE_AAAA => {
+                let octets: [u8; 16] = data.try_into().ok()?;
+                records.push(Record::Aaaa(Ipv6Addr::from(octets)));
+            },
+            TYPE_CNAME => records.push(Record::Cname(read_name(message, data_start)?)),
+            _ => {}
+        }
+
+        offset = data_start + data_len;
+    }
+
+    Some(Response { id, code, records })
+}
+
+fn read_u16(message: &[u8], offset: usize) -> Option<u16> {
+    let bytes = message.get(offset .. offset + 2)?;
+
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+/// Returns the offset just past the name starting at `offset`.
+fn skip_name(message: &[u8], mut offset: usize) -> Option<usize> {
+    loop {
+        let len = *message.get(offset)? as usize;
+
+        match len {
+            0 => return Some(offset + 1),
+            // a compression pointer always ends the name
+            len if len & 0xC0 == 0xC0 => return Some(offset + 2),
+            len => offset += len + 1
+        }
+    }
+}
+
+/// Reads the name starting at `offset`, following compression pointers.
+fn read_name(message: &[u8], mut offset: usize) -> Option<String> {
+    let mut labels: Vec<String> = Vec::new();
+    let mut pointers = 0;
+
+    loop {
+        let len = *message.get(offset)? as usize;
+
+        if len == 0 {
+            return Some(labels.join("."));
+        }
+
+        if len & 0xC0 == 0xC0 {
+            pointers += 1;
+
+            // guards against pointer loops in malicious responses
+            if pointers > MAX_POINTERS {
+                return None;
+            }
+
+            offset = (read_u16(message, offset)? & 0x3FFF) as usize;
+            continue;
+        }
+
+        let label = message.get(offset + 1 .. offset + 1 + len)?;
+        labels.push(String::from_utf8_lossy(label).to_lowercase());
+        offset += len + 1;
+    }
+}
@@ -0,0 +1,128 @@
+//! Drives generation over several rounds, feeding the names confirmed after each round back in as
+//! new domains while never emitting the same name twice.
+
+use fxhash::FxHashSet;
+use crate::{RipGenError, RipGenManager, WordScope};
+use crate::shard::Shard;
+
+/// Owns the domains and words used across rounds along with every name emitted so far.
+///
+/// ```
+/// # use ripgen_lib::RipGenIterator;
+/// # use ripgen_lib::rounds::RoundDriver;
+/// # use ripgen_lib::variants::label_deletion_transform;
+/// let mut driver = RoundDriver::new(vec!["a.b.example.com"].into_iter(), std::iter::empty::<&str>());
+///
+/// let first: Vec<String> = driver.run_round(&|_| true, |manager, is_new| {
+///     manager.transform(label_deletion_transform).filter(|name| is_new(name)).collect()
+/// }).expect("Failed to parse domains.");
+///
+/// assert_eq!(first.len(), 2);
+///
+/// driver.add_confirmed(vec!["a.b.c.example.com"].into_iter());
+///
+/// let mut second: Vec<String> = driver.run_round(&|_| true, |manager, is_new| {
+///     manager.transform(label_deletion_transform).filter(|name| is_new(name)).collect()
+/// }).expect("Failed to parse domains.");
+/// second.sort();
+///
+/// // a.example.com and b.example.com were already emitted in the first round
+/// assert_eq!(second, vec!["a.c.example.com", "b.c.example.com"]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RoundDriver {
+    domains: Vec<String>,
+    words: Vec<String>,
+    word_weights: Vec<(String, f64)>,
+    emitted: FxHashSet<String>,
+    rounds: usize,
+    shard: Option<Shard>,
+    word_scope: WordScope
+}
+
+impl RoundDriver {
+    pub fn new<D: Into<String>, W: Into<String>>(
+        domains: impl Iterator<Item = D>,
+        words: impl Iterator<Item = W>
+    ) -> Self {
+        let mut driver = Self {
+            words: words.map(Into::into).collect(),
+            ..Self::default()
+        };
+
+        driver.add_confirmed(domains);
+        driver
+    }
+
+    /// Only generates the given [Shard](crate::shard::Shard) in every round. Every worker must be
+    /// given the same confirmed names between rounds for the shards to stay disjoint.
+    pub fn with_shard(mut self, shard: Shard) -> Self {
+        self.shard = Some(shard);
+        self
+    }
+
+    /// Sets which words each domain is permuted with in every round, see [WordScope](crate::WordScope).
+    pub fn with_word_scope(mut self, word_scope: WordScope) -> Self {
+        self.word_scope = word_scope;
+        self
+    }
+
+    /// Sets the weight of words in every round, see [word_weights](crate::RipGenManagerBuilder::word_weights).
+    pub fn with_word_weights<W: Into<String>>(mut self, weights: impl Iterator<Item = (W, f64)>) -> Self {
+        self.word_weights = weights.map(|(word, weight)| (word.into(), weight)).collect();
+        self
+    }
+
+    /// Adds confirmed names as domains for the following rounds. Returns how many were new.
+    pub fn add_confirmed<D: Into<String>>(&mut self, names: impl Iterator<Item = D>) -> usize {
+        let mut added = 0;
+
+        for name in names {
+            let name: String = name.into();
+            let name = name.trim().trim_end_matches('.');
+
+            // known names are never worth emitting, so they share the emitted set
+            if !name.is_empty() && self.emitted.insert(name.to_string()) {
+                self.domains.push(name.to_string());
+                added += 1;
+            }
+        }
+
+        added
+    }
+
+    /// Returns the number of rounds that have been run.
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    /// Runs a round over a manager created from every domain and word so far.
+    ///
+    /// `round` receives the manager along with an `is_new` function that returns whether a name
+    /// hasn't been emitted in this or any earlier round, recording it as emitted.
+    pub fn run_round<F, R>(&mut self, word_filter: &impl Fn(&&str) -> bool, round: F) -> Result<R, RipGenError>
+        where
+            F: for<'manager> FnOnce(&'manager RipGenManager<'manager>, &mut dyn FnMut(&str) -> bool) -> R
+    {
+        let mut builder = RipGenManager::builder()
+            .domains(self.domains.iter().map(String::as_str))
+            .words(self.words.iter().map(String::as_str))
+            .word_filter(word_filter)
+            .word_weights(self.word_weights.iter().map(|(word, weight)| (word.as_str(), *weight)))
+            .word_scope(self.word_scope);
+
+        if let Some(shard) = self.shard {
+            builder = builder.shard(shard);
+        }
+
+        let manager = builder.build()?;
+
+        let emitted = &mut self.emitted;
+        let mut is_new = |name: &str| !emitted.contains(name) && emitted.insert(name.to_string());
+
+        let result = round(&manager, &mut is_new);
+        self.rounds += 1;
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,185 @@
+//! Splits the generation space into disjoint slices so several workers can generate without
+//! coordinating.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use crate::{DomainComponents, RipGenError};
+use crate::pools::WordPools;
+use crate::words::WordSet;
+
+/// One of `count` disjoint slices of the generation space, written as `index/count` with `index`
+/// starting at 1.
+///
+/// Every name is produced from a single domain, so transforms are split by domain: each shard runs
+/// them over every `count`th domain with every word. Transforms that use a single word for each
+/// name, run with [transform_per_word](crate::RipGenManager::transform_per_word) or
+/// [chain_transform_per_word](crate::RipGenIterator::chain_transform_per_word), are split on pairs
+/// of a domain and a word instead, so that the shards still share the work when there are fewer
+/// domains than shards. The domains are then split into the largest number of slices that divides
+/// `count` and isn't above the number of domains, the words into `count` divided by that, and each
+/// shard pairs one domain slice with one word slice.
+///
+/// Every worker must be given the same domains and words. Together, the shards produce exactly the
+/// output of an unsharded manager.
+///
+/// ```
+/// # use ripgen_lib::shard::Shard;
+/// let shard: Shard = "2/4".parse().expect("Failed to parse shard.");
+///
+/// assert_eq!(shard.index(), 2);
+/// assert_eq!(shard.count(), 4);
+/// assert!("5/4".parse::<Shard>().is_err());
+/// ```
+///
+/// ```
+/// # use ripgen_lib::{DomainComponents, RipGenIterator, RipGenManager, WordlistIterator};
+/// # use ripgen_lib::shard::Shard;
+/// # use ripgen_lib::variants::{depth_permute_transform, token_swap_transform};
+/// # fn dash_transform<'domain>(domain_components: &'domain DomainComponents, words: WordlistIterator<'domain>) -> impl Iterator<Item = String> + 'domain {
+/// #     let (first, rest) = domain_components.all().split_first().expect("Empty domain.");
+/// #     words.map(move |word| format!("{}-{}.{}", first, word, rest.join(".")))
+/// # }
+/// let domains = vec!["api-dev.example.com", "www.example.com"];
+/// let words = vec!["dev", "prod", "stage"];
+///
+/// let generate = |shard: Option<Shard>| {
+///     let mut manager = RipGenManager::new(domains.iter().copied(), words.iter().copied(), &|_| false)
+///         .expect("Failed to parse domains.");
+///
+///     if let Some(shard) = shard {
+///         manager = manager.with_shard(shard);
+///     }
+///
+///     manager
+///         .transform_per_word(None, depth_permute_transform(1, 0, None))
+///         .chain_transform(token_swap_transform)
+///         .chain_transform_per_word(None, dash_transform)
+///         .chain_transform(depth_permute_transform(2, 0, None))
+///         .collect::<Vec<String>>()
+/// };
+///
+/// let mut unsharded = generate(None);
+/// unsharded.sort();
+///
+/// for count in 1 ..= 5 {
+///     let mut union: Vec<String> = (1 ..= count)
+///         .flat_map(|index| generate(Some(Shard::new(index, count).expect("Invalid shard."))))
+///         .collect();
+///     union.sort();
+///
+///     // duplicates included, so no name comes from more than one shard
+///     assert_eq!(union, unsharded);
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Shard {
+    index: usize,
+    count: usize
+}
+
+impl Shard {
+    /// Creates the `index`th of `count` shards, with `index` starting at 1.
+    pub fn new(index: usize, count: usize) -> Result<Self, RipGenError> {
+        if count == 0 || index == 0 || index > count {
+            return Err(RipGenError::InvalidShard(format!("{}/{}", index, count)));
+        }
+
+        Ok(Self { index, count })
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns whether the item at `position` in a sequence every worker sees identically belongs
+    /// to this shard.
+    pub fn contains(&self, position: usize) -> bool {
+        position % self.count == self.index - 1
+    }
+
+    /// Selects this shard's domains and words.
+    pub(crate) fn slice<'domain>(
+        &self,
+        domains: &[DomainComponents<'domain>],
+        words: &WordSet<'domain>,
+        pools: Option<&WordPools<'domain>>
+    ) -> ShardSlice<'domain> {
+        let domain_shards = (1 ..= self.count)
+            .rev()
+            .find(|shards| self.count.is_multiple_of(*shards) && *shards <= domains.len())
+            .unwrap_or(1);
+        let word_shards = self.count / domain_shards;
+
+        let domain_slice = (self.index - 1) % domain_shards;
+        let word_slice = (self.index - 1) / domain_shards;
+
+        let per_word_domains = domains
+            .iter()
+            .enumerate()
+            .filter(|(position, _)| position % domain_shards == domain_slice)
+            .map(|(_, domain)| domain.clone())
+            .collect();
+
+        // every worker orders the words the same, so their positions can be split
+        let mut per_word_words = words.clone();
+        per_word_words.retain_positions(|position| position % word_shards == word_slice);
+
+        let per_word_pools = pools.map(|pools| {
+            let mut pools = pools.clone();
+            pools.retain_positions(|position| position % word_shards == word_slice);
+            pools
+        });
+
+        let domains = domains
+            .iter()
+            .enumerate()
+            .filter(|(position, _)| self.contains(*position))
+            .map(|(_, domain)| domain.clone())
+            .collect();
+
+        ShardSlice {
+            shard: *self,
+            domains,
+            per_word_domains,
+            per_word_words,
+            per_word_pools
+        }
+    }
+}
+
+impl Display for Shard {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.index, self.count)
+    }
+}
+
+impl FromStr for Shard {
+    type Err = RipGenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || RipGenError::InvalidShard(s.to_string());
+
+        let (index, count) = s.split_once('/').ok_or_else(invalid)?;
+        let index = index.trim().parse().map_err(|_| invalid())?;
+        let count = count.trim().parse().map_err(|_| invalid())?;
+
+        Shard::new(index, count)
+    }
+}
+
+/// The domains and words of a single shard.
+#[derive(Clone)]
+pub(crate) struct ShardSlice<'domain> {
+    pub(crate) shard: Shard,
+    /// Every `count`th domain, for transforms split by domain.
+    pub(crate) domains: Vec<DomainComponents<'domain>>,
+    /// The slice of the domains and the slice of the words, or of the words of each pool if words
+    /// are scoped, for transforms split on pairs of a domain and a word.
+    pub(crate) per_word_domains: Vec<DomainComponents<'domain>>,
+    pub(crate) per_word_words: WordSet<'domain>,
+    pub(crate) per_word_pools: Option<WordPools<'domain>>
+}
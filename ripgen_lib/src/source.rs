@@ -0,0 +1,28 @@
+use std::slice::Iter as SliceIter;
+use crate::words::WordSet;
+
+/// The words a single transform is permuted with instead of the words of its chain. Created with
+/// [RipGenManager::word_source](crate::RipGenManager::word_source) and paired with a transform
+/// through [chain_transform_with_words](crate::RipGenIterator::chain_transform_with_words).
+#[derive(Clone, Debug, Default)]
+pub struct WordSource<'domain> {
+    pub(crate) words: WordSet<'domain>
+}
+
+impl<'domain> WordSource<'domain> {
+    pub fn iter(&self) -> SliceIter<'_, &'domain str> {
+        self.words.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.len() == 0
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(word)
+    }
+}
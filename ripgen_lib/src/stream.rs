@@ -0,0 +1,150 @@
+//! Exposes generation as a [Stream] so it can be driven from an async executor without blocking it.
+//!
+//! Names are pulled from the underlying iterator in batches. Between batches the stream yields to
+//! the executor, and nothing is generated until the consumer polls for more, so a slow consumer
+//! holds back generation.
+
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use futures_core::Stream;
+
+const DEFAULT_BATCH_SIZE: usize = 1024;
+
+/// A [Stream] of the names produced by an iterator, such as a [RipGenIterator](crate::RipGenIterator) chain.
+///
+/// A single domain's output of one transform is still generated in one go, so the batch size
+/// bounds how many names are generated per poll rather than how long a poll takes.
+///
+/// ```
+/// # use futures::StreamExt;
+/// # use ripgen_lib::RipGenManager;
+/// # use ripgen_lib::stream::RipGenStream;
+/// # use ripgen_lib::variants::label_deletion_transform;
+/// let domains = vec!["a.b.example.com", "c.d.example.com"];
+/// let manager = RipGenManager::new(domains.into_iter(), std::iter::empty(), &|_| true)
+///     .expect("Failed to parse domains.");
+///
+/// let stream = RipGenStream::new(manager.transform(label_deletion_transform)).with_batch_size(3);
+///
+/// let mut batches: Vec<Vec<String>> = futures::executor::block_on(stream.batches().collect());
+/// batches.iter_mut().for_each(|batch| batch.sort());
+///
+/// assert_eq!(batches, vec![
+///     vec!["a.example.com", "b.example.com", "d.example.com"],
+///     vec!["c.example.com"]
+/// ]);
+/// ```
+pub struct RipGenStream<I> {
+    iter: Option<I>,
+    batch_size: usize,
+    buffer: VecDeque<String>,
+    yield_next: bool
+}
+
+impl<I> RipGenStream<I>
+    where
+        I: Iterator<Item = String>
+{
+    pub fn new(iter: I) -> Self {
+        Self {
+            iter: Some(iter),
+            batch_size: DEFAULT_BATCH_SIZE,
+            buffer: VecDeque::new(),
+            yield_next: false
+        }
+    }
+
+    /// Sets how many names are generated each time the stream runs out of buffered names.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Turns this into a stream of whole batches rather than single names.
+    pub fn batches(self) -> RipGenBatches<I> {
+        RipGenBatches { inner: self }
+    }
+
+    /// Polls for the next batch, yielding to the executor once after every batch. Returns an
+    /// empty batch once the iterator is exhausted.
+    fn poll_batch(&mut self, cx: &mut Context<'_>) -> Poll<Vec<String>> {
+        if self.yield_next {
+            self.yield_next = false;
+            cx.waker().wake_by_ref();
+
+            return Poll::Pending;
+        }
+
+        let batch: Vec<String> = match self.iter {
+            Some(ref mut iter) => iter.by_ref().take(self.batch_size).collect(),
+            None => Vec::new()
+        };
+
+        if batch.len() < self.batch_size {
+            self.iter = None;
+        }
+
+        self.yield_next = !batch.is_empty();
+
+        Poll::Ready(batch)
+    }
+}
+
+impl<I> Stream for RipGenStream<I>
+    where
+        I: Iterator<Item = String> + Unpin
+{
+    type Item = String;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        if let Some(name) = this.buffer.pop_front() {
+            return Poll::Ready(Some(name));
+        }
+
+        match this.poll_batch(cx) {
+            Poll::Ready(batch) => {
+                this.buffer.extend(batch);
+
+                Poll::Ready(this.buffer.pop_front())
+            },
+            Poll::Pending => Poll::Pending
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.iter {
+            Some(ref iter) => (self.buffer.len(), iter.size_hint().1.map(|upper| upper + self.buffer.len())),
+            None => (self.buffer.len(), Some(self.buffer.len()))
+        }
+    }
+}
+
+/// A [Stream] of batches of names, see [RipGenStream::batches].
+pub struct RipGenBatches<I> {
+    inner: RipGenStream<I>
+}
+
+impl<I> Stream for RipGenBatches<I>
+    where
+        I: Iterator<Item = String> + Unpin
+{
+    type Item = Vec<String>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let inner = &mut self.get_mut().inner;
+
+        // names already buffered by the single name stream go out first
+        if !inner.buffer.is_empty() {
+            return Poll::Ready(Some(inner.buffer.drain(..).collect()));
+        }
+
+        match inner.poll_batch(cx) {
+            Poll::Ready(batch) if batch.is_empty() => Poll::Ready(None),
+            Poll::Ready(batch) => Poll::Ready(Some(batch)),
+            Poll::Pending => Poll::Pending
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use crate::{DomainComponents, RipGenIterator};
+use crate::{DomainComponents, RipGenIterator, WordSplit, WordsFor};
+use crate::checkpoint::{Checkpoint, Resumable};
 
 pub struct RipGenTransform<'manager, 'domain, F, D, W, O>
     where
@@ -10,8 +11,16 @@ pub struct RipGenTransform<'manager, 'domain, F, D, W, O>
 {
     domains: D,
     words: W,
+    words_for: Option<WordsFor<'manager, 'domain, W>>,
+    word_split: Option<WordSplit<'manager, 'domain, D, W>>,
+    /// Whether the transform runs over its `word_split` rather than its domains and words.
+    per_word: bool,
     result_pool: Vec<String>,
-    transform: F
+    transform: F,
+    /// The number of domains taken so far.
+    domains_taken: usize,
+    /// The number of results taken from the last domain.
+    results_taken: usize
 }
 
 impl<'manager, 'domain, F, D, W, O> RipGenTransform<'manager, 'domain, F, D, W, O>
@@ -26,8 +35,40 @@ impl<'manager, 'domain, F, D, W, O> RipGenTransform<'manager, 'domain, F, D, W,
         Self {
             domains,
             words,
+            words_for: None,
+            word_split: None,
+            per_word: false,
             result_pool: Vec::with_capacity(1024 * 4),
-            transform
+            transform,
+            domains_taken: 0,
+            results_taken: 0
+        }
+    }
+
+    /// Permutes each domain with the words `words_for` returns for it instead of every word.
+    pub(crate) fn with_words_for(mut self, words_for: Option<WordsFor<'manager, 'domain, W>>) -> Self {
+        self.words_for = words_for;
+        self
+    }
+
+    /// Passes the domains and words for transforms that use a single word for each name on to the
+    /// transforms chained after this one.
+    pub(crate) fn with_word_split(mut self, word_split: Option<WordSplit<'manager, 'domain, D, W>>) -> Self {
+        self.word_split = word_split;
+        self
+    }
+
+    /// Runs the transform over the domains and words of its [WordSplit], if any, while the
+    /// transforms chained after it still get its own.
+    pub(crate) fn per_word(mut self) -> Self {
+        self.per_word = self.word_split.is_some();
+        self
+    }
+
+    fn domains_mut(&mut self) -> &mut D {
+        match self.word_split {
+            Some(ref mut split) if self.per_word => &mut split.domains,
+            _ => &mut self.domains
         }
     }
 }
@@ -45,21 +86,60 @@ impl<'manager, 'domain, F, D, W, O> Iterator for RipGenTransform<'manager, 'doma
 
     fn next(&mut self) -> Option<Self::Item> {
         if let Some(next) = self.result_pool.pop() {
+            self.results_taken += 1;
             return Some(next);
         }
 
         loop {
-            let domain = self.domains.next()?;
-
-            self.result_pool.extend((self.transform)(domain, self.words.clone()));
+            if !self.next_domain() {
+                return None;
+            }
 
             if let Some(result) = self.result_pool.pop() {
+                self.results_taken += 1;
                 return Some(result);
             }
         }
     }
 }
 
+impl<'manager, 'domain, F, D, W, O> RipGenTransform<'manager, 'domain, F, D, W, O>
+    where
+        F: Fn(&'manager DomainComponents<'domain>, W) -> O,
+        D: Iterator<Item = &'manager DomainComponents<'domain>> + Clone,
+        W: Iterator<Item = &'manager &'domain str> + Clone,
+        O: Iterator<Item = String>,
+        'domain: 'manager
+{
+    /// Fills the result pool with the output of the next domain. Returns false if there are no
+    /// domains left.
+    fn next_domain(&mut self) -> bool {
+        let (domains, words, words_for) = match self.word_split {
+            Some(ref mut split) if self.per_word => (&mut split.domains, &split.words, &split.words_for),
+            _ => (&mut self.domains, &self.words, &self.words_for)
+        };
+
+        let domain = match domains.next() {
+            Some(domain) => domain,
+            None => return false
+        };
+
+        self.domains_taken += 1;
+        self.results_taken = 0;
+
+        let words = match words_for {
+            Some(ref words_for) => words_for(domain),
+            None => words.clone()
+        };
+
+        // results are popped off the end, so they're stored reversed to come out in order
+        self.result_pool.extend((self.transform)(domain, words));
+        self.result_pool.reverse();
+
+        true
+    }
+}
+
 
 impl<'manager, 'domain, F, D, W, O> RipGenIterator<'manager, 'domain, D, W> for RipGenTransform<'manager, 'domain, F, D, W, O>
     where
@@ -76,4 +156,55 @@ impl<'manager, 'domain, F, D, W, O> RipGenIterator<'manager, 'domain, D, W> for
     fn get_words_iter(&self) -> W {
         self.words.clone()
     }
-}
\ No newline at end of file
+
+    fn get_word_split(&self) -> Option<WordSplit<'manager, 'domain, D, W>> {
+        self.word_split.clone()
+    }
+
+    fn get_words_for(&self) -> Option<WordsFor<'manager, 'domain, W>> {
+        self.words_for.clone()
+    }
+}
+
+impl<'manager, 'domain, F, D, W, O> Resumable for RipGenTransform<'manager, 'domain, F, D, W, O>
+    where
+        F: Fn(&'manager DomainComponents<'domain>, W) -> O,
+        D: Iterator<Item = &'manager DomainComponents<'domain>> + Clone,
+        W: Iterator<Item = &'manager &'domain str> + Clone,
+        O: Iterator<Item = String>,
+        'domain: 'manager
+{
+    const TRANSFORMS: usize = 1;
+
+    fn checkpoint(&self) -> Checkpoint {
+        // nothing has been taken from the last domain, so resuming can start after it
+        match self.results_taken {
+            0 => Checkpoint { transform: 0, domain: self.domains_taken, offset: 0 },
+            taken => Checkpoint { transform: 0, domain: self.domains_taken - 1, offset: taken }
+        }
+    }
+
+    fn resume(&mut self, checkpoint: Checkpoint) {
+        self.result_pool.clear();
+
+        if checkpoint.transform > 0 {
+            self.domains_mut().for_each(drop);
+            return;
+        }
+
+        for _ in 0 .. checkpoint.domain {
+            if self.domains_mut().next().is_none() {
+                return;
+            }
+
+            self.domains_taken += 1;
+        }
+
+        if checkpoint.offset > 0 && self.next_domain() {
+            let remaining = self.result_pool.len().saturating_sub(checkpoint.offset);
+
+            self.result_pool.truncate(remaining);
+            self.results_taken = checkpoint.offset;
+        }
+    }
+}
@@ -0,0 +1,41 @@
+/// Returns characters that are visually similar to `ch`. Most of these are outside of ASCII and
+/// require the label to be punycode encoded.
+pub(crate) fn homoglyphs(ch: char) -> &'static [char] {
+    match ch {
+        'a' => &['а', 'à', 'á', 'â', 'ã', 'ä', 'å', 'ɑ'],
+        'b' => &['ḃ', 'ḅ', 'ƅ'],
+        'c' => &['с', 'ç', 'ć', 'ċ'],
+        'd' => &['ԁ', 'ď', 'ḋ'],
+        'e' => &['е', 'è', 'é', 'ê', 'ë', 'ė'],
+        'g' => &['ġ', 'ǵ', 'ɡ'],
+        'h' => &['һ', 'ḣ'],
+        'i' => &['і', 'ì', 'í', 'î', 'ï', 'ı', '1', 'l'],
+        'j' => &['ј'],
+        'k' => &['κ', 'ḳ'],
+        'l' => &['ӏ', 'ḷ', '1', 'i'],
+        'm' => &['ṃ'],
+        'n' => &['ń', 'ñ', 'ṅ'],
+        'o' => &['о', 'ò', 'ó', 'ô', 'õ', 'ö', 'ο', '0'],
+        'p' => &['р', 'ṗ'],
+        'q' => &['ԛ'],
+        'r' => &['ŗ', 'ṙ'],
+        's' => &['ѕ', 'ś', 'ṡ'],
+        't' => &['ţ', 'ṫ'],
+        'u' => &['υ', 'ù', 'ú', 'û', 'ü'],
+        'v' => &['ν', 'ѵ'],
+        'w' => &['ԝ', 'ẁ', 'ẃ'],
+        'x' => &['х', 'ẋ'],
+        'y' => &['у', 'ý', 'ÿ'],
+        'z' => &['ż', 'ź', 'ẓ'],
+        _ => &[]
+    }
+}
+
+/// Converts a label into its ASCII form, punycode encoding it if needed.
+pub(crate) fn to_ascii_label(label: &str) -> Option<String> {
+    if label.is_ascii() {
+        return Some(label.to_string());
+    }
+
+    idna::punycode::encode_str(label).map(|encoded| format!("xn--{encoded}"))
+}
@@ -0,0 +1,42 @@
+/// Returns the keys that neighbour `key` on a QWERTY keyboard.
+pub(crate) fn adjacent_keys(key: char) -> &'static str {
+    match key {
+        '1' => "2q",
+        '2' => "3wq1",
+        '3' => "4ew2",
+        '4' => "5re3",
+        '5' => "6tr4",
+        '6' => "7yt5",
+        '7' => "8uy6",
+        '8' => "9iu7",
+        '9' => "0oi8",
+        '0' => "po9",
+        'q' => "12wa",
+        'w' => "3esaq2",
+        'e' => "4rdsw3",
+        'r' => "5tfde4",
+        't' => "6ygfr5",
+        'y' => "7uhgt6",
+        'u' => "8ijhy7",
+        'i' => "9okju8",
+        'o' => "0plki9",
+        'p' => "lo0",
+        'a' => "qwsz",
+        's' => "edxzaw",
+        'd' => "rfcxse",
+        'f' => "tgvcdr",
+        'g' => "yhbvft",
+        'h' => "ujnbgy",
+        'j' => "ikmnhu",
+        'k' => "olmji",
+        'l' => "kop",
+        'z' => "asx",
+        'x' => "zsdc",
+        'c' => "xdfv",
+        'v' => "cfgb",
+        'b' => "vghn",
+        'n' => "bhjm",
+        'm' => "njk",
+        _ => ""
+    }
+}
@@ -0,0 +1,218 @@
+//! Typosquatting transforms that mutate the registrable label of a domain (e.g. `google` in
+//! `www.google.co.uk`) while keeping its subdomains and public suffix.
+
+mod keyboard;
+mod homoglyph;
+
+use fxhash::FxHashSet;
+use crate::{DomainComponents, WordlistIterator};
+
+const VOWELS: &[char] = &['a', 'e', 'i', 'o', 'u'];
+const MAX_LABEL_LEN: usize = 63;
+
+/// Produces every variant of a label for a single typo technique.
+type Mutation = fn(&[char]) -> Vec<String>;
+
+/// Applies every typo transform in this module.
+pub fn typo_transform(
+    domain_components: &DomainComponents,
+    _: WordlistIterator
+) -> std::vec::IntoIter<String> {
+    typo_components(domain_components, &[
+        omission,
+        repetition,
+        adjacent_key,
+        transposition,
+        bit_flip,
+        vowel_swap,
+        hyphenation,
+        homoglyph
+    ])
+}
+
+/// Removes one character at a time (`google` -> `gogle`).
+///
+/// ```
+/// # use ripgen_lib::RipGenManager;
+/// # use ripgen_lib::typo::omission_transform;
+/// let domains = vec!["www.abc.co.uk"];
+/// let manager = RipGenManager::new(domains.into_iter(), std::iter::empty(), &|_| true)
+///     .expect("Failed to parse domains.");
+///
+/// let mut output: Vec<String> = manager
+///     .transform(omission_transform)
+///     .collect();
+/// output.sort();
+///
+/// assert_eq!(output, vec!["www.ab.co.uk", "www.ac.co.uk", "www.bc.co.uk"]);
+/// ```
+pub fn omission_transform(domain_components: &DomainComponents, _: WordlistIterator) -> std::vec::IntoIter<String> {
+    typo_components(domain_components, &[omission])
+}
+
+/// Repeats one character at a time (`google` -> `gooogle`).
+pub fn repetition_transform(domain_components: &DomainComponents, _: WordlistIterator) -> std::vec::IntoIter<String> {
+    typo_components(domain_components, &[repetition])
+}
+
+/// Replaces one character at a time with its neighbours on a QWERTY keyboard (`google` -> `foogle`).
+pub fn adjacent_key_transform(domain_components: &DomainComponents, _: WordlistIterator) -> std::vec::IntoIter<String> {
+    typo_components(domain_components, &[adjacent_key])
+}
+
+/// Swaps adjacent characters (`google` -> `ogogle`).
+pub fn transposition_transform(domain_components: &DomainComponents, _: WordlistIterator) -> std::vec::IntoIter<String> {
+    typo_components(domain_components, &[transposition])
+}
+
+/// Flips a single bit of one character at a time, keeping results that are valid in a hostname
+/// (`google` -> `coogle`).
+pub fn bit_flip_transform(domain_components: &DomainComponents, _: WordlistIterator) -> std::vec::IntoIter<String> {
+    typo_components(domain_components, &[bit_flip])
+}
+
+/// Replaces one vowel at a time with every other vowel (`google` -> `gaogle`).
+pub fn vowel_swap_transform(domain_components: &DomainComponents, _: WordlistIterator) -> std::vec::IntoIter<String> {
+    typo_components(domain_components, &[vowel_swap])
+}
+
+/// Inserts a dash between characters (`google` -> `goo-gle`).
+pub fn hyphenation_transform(domain_components: &DomainComponents, _: WordlistIterator) -> std::vec::IntoIter<String> {
+    typo_components(domain_components, &[hyphenation])
+}
+
+/// Replaces one character at a time with a lookalike character (`google` -> `gоogle`). Labels that
+/// end up containing non-ASCII characters are emitted in punycode (`xn--gogle-jye`).
+pub fn homoglyph_transform(domain_components: &DomainComponents, _: WordlistIterator) -> std::vec::IntoIter<String> {
+    typo_components(domain_components, &[homoglyph])
+}
+
+/// Runs each of the `mutations` against the registrable label and rebuilds the full names.
+fn typo_components(
+    domain_components: &DomainComponents,
+    mutations: &[Mutation]
+) -> std::vec::IntoIter<String> {
+    let registrable_name = domain_components
+        .root()
+        .strip_suffix(domain_components.suffix())
+        .and_then(|name| name.strip_suffix('.'))
+        .filter(|name| !name.is_empty());
+
+    let registrable_name = match registrable_name {
+        Some(name) => name.to_lowercase(),
+        None => return Vec::new().into_iter()
+    };
+
+    let chars: Vec<char> = registrable_name.chars().collect();
+    let mut seen: FxHashSet<String> = FxHashSet::default();
+
+    mutations
+        .iter()
+        .flat_map(|mutation| mutation(&chars))
+        .filter_map(|label| homoglyph::to_ascii_label(&label))
+        .filter(|label| is_valid_label(label) && *label != registrable_name)
+        .filter(|label| seen.insert(label.clone()))
+        .map(|label| {
+            let new_sub: Vec<&str> = [
+                domain_components.subdomains(),
+                [label.as_str(), domain_components.suffix()].as_slice()
+            ].concat();
+
+            new_sub.join(".")
+        })
+        .collect::<Vec<String>>()
+        .into_iter()
+}
+
+fn is_valid_label(label: &str) -> bool {
+    !label.is_empty()
+        && label.len() <= MAX_LABEL_LEN
+        && !label.starts_with('-')
+        && !label.ends_with('-')
+        && label.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '-')
+}
+
+/// Replaces the character at `idx` with `replacement`.
+fn replace_at(chars: &[char], idx: usize, replacement: &[char]) -> String {
+    chars[.. idx]
+        .iter()
+        .chain(replacement)
+        .chain(&chars[idx + 1 ..])
+        .collect()
+}
+
+fn omission(chars: &[char]) -> Vec<String> {
+    (0 .. chars.len())
+        .map(|idx| replace_at(chars, idx, &[]))
+        .collect()
+}
+
+fn repetition(chars: &[char]) -> Vec<String> {
+    (0 .. chars.len())
+        .filter(|idx| chars[*idx] != '-')
+        .map(|idx| replace_at(chars, idx, &[chars[idx], chars[idx]]))
+        .collect()
+}
+
+fn adjacent_key(chars: &[char]) -> Vec<String> {
+    (0 .. chars.len())
+        .flat_map(|idx| {
+            keyboard::adjacent_keys(chars[idx])
+                .chars()
+                .map(move |key| replace_at(chars, idx, &[key]))
+        })
+        .collect()
+}
+
+fn transposition(chars: &[char]) -> Vec<String> {
+    (1 .. chars.len())
+        .filter(|idx| chars[idx - 1] != chars[*idx])
+        .map(|idx| {
+            let mut swapped = chars.to_vec();
+            swapped.swap(idx - 1, idx);
+
+            swapped.into_iter().collect()
+        })
+        .collect()
+}
+
+fn bit_flip(chars: &[char]) -> Vec<String> {
+    (0 .. chars.len())
+        .filter(|idx| chars[*idx].is_ascii())
+        .flat_map(|idx| {
+            (0 .. 8)
+                .map(move |bit| (chars[idx] as u8 ^ (1 << bit)) as char)
+                .filter(|flipped| flipped.is_ascii_lowercase() || flipped.is_ascii_digit() || *flipped == '-')
+                .map(move |flipped| replace_at(chars, idx, &[flipped]))
+        })
+        .collect()
+}
+
+fn vowel_swap(chars: &[char]) -> Vec<String> {
+    (0 .. chars.len())
+        .filter(|idx| VOWELS.contains(&chars[*idx]))
+        .flat_map(|idx| {
+            VOWELS
+                .iter()
+                .filter(move |vowel| **vowel != chars[idx])
+                .map(move |vowel| replace_at(chars, idx, &[*vowel]))
+        })
+        .collect()
+}
+
+fn hyphenation(chars: &[char]) -> Vec<String> {
+    (1 .. chars.len())
+        .filter(|idx| chars[idx - 1] != '-' && chars[*idx] != '-')
+        .map(|idx| replace_at(chars, idx, &['-', chars[idx]]))
+        .collect()
+}
+
+fn homoglyph(chars: &[char]) -> Vec<String> {
+    (0 .. chars.len())
+        .flat_map(|idx| {
+            homoglyph::homoglyphs(chars[idx])
+                .iter()
+                .map(move |glyph| replace_at(chars, idx, &[*glyph]))
+        })
+        .collect()
+}
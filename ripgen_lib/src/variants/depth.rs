@@ -0,0 +1,142 @@
+use fxhash::FxHashSet;
+use crate::{DomainComponents, WordlistIterator};
+use crate::words::split_segments;
+
+/// Inserts up to `depth` words into every domain, one after another, at every position before the
+/// root. A depth of 1 behaves like dnsgen's `permute_words_transform`.
+///
+/// When `number_offsets` is non-zero, each inserted word is also tried with the numbers in it
+/// increased and decreased by up to that amount (`api2` -> `api1`, `api3`), keeping any zero padding
+/// (`db01` -> `db00`, `db02`). At most `per_domain_cap` unique names are produced for a single
+/// domain, shallower names first. Every name for a domain is built before the first is returned,
+/// so a cap is needed from a depth of 2.
+///
+/// ```
+/// # use ripgen_lib::RipGenManager;
+/// # use ripgen_lib::variants::depth_permute_transform;
+/// let domains = vec!["www.example.com"];
+/// let words = vec!["dev"];
+/// let manager = RipGenManager::new(domains.into_iter(), words.into_iter(), &|_| false)
+///     .expect("Failed to parse domains.");
+///
+/// let mut output: Vec<String> = manager
+///     .transform(depth_permute_transform(2, 0, None))
+///     .collect();
+/// output.sort();
+///
+/// assert_eq!(output, vec![
+///     "dev.dev.www.example.com",
+///     "dev.www.dev.example.com",
+///     "dev.www.example.com",
+///     "www.dev.dev.example.com",
+///     "www.dev.example.com"
+/// ]);
+///
+/// let manager = RipGenManager::new(vec!["example.com"].into_iter(), vec!["db01", "api9"].into_iter(), &|_| false)
+///     .expect("Failed to parse domains.");
+///
+/// let mut output: Vec<String> = manager
+///     .transform(depth_permute_transform(1, 1, None))
+///     .collect();
+/// output.sort();
+///
+/// assert_eq!(output, vec![
+///     "api10.example.com",
+///     "api8.example.com",
+///     "api9.example.com",
+///     "db00.example.com",
+///     "db01.example.com",
+///     "db02.example.com"
+/// ]);
+/// ```
+pub fn depth_permute_transform(
+    depth: usize,
+    number_offsets: u64,
+    per_domain_cap: Option<usize>
+) -> impl Fn(&DomainComponents, WordlistIterator) -> std::vec::IntoIter<String> {
+    move |domain_components: &DomainComponents, words: WordlistIterator| -> std::vec::IntoIter<String> {
+        let cap = per_domain_cap.unwrap_or(usize::MAX);
+
+        let words: Vec<String> = words
+            .flat_map(|word| number_variants(word, number_offsets))
+            .collect();
+
+        let mut seen: FxHashSet<String> = FxHashSet::default();
+        let mut results: Vec<String> = Vec::new();
+        let mut level: Vec<Vec<&str>> = vec![domain_components.all().to_vec()];
+
+        for _ in 0 .. depth {
+            let mut next_level: Vec<Vec<&str>> = Vec::new();
+
+            for components in &level {
+                // inserting before the root is allowed, but never after it
+                for idx in 0 .. components.len() {
+                    for word in &words {
+                        if results.len() >= cap {
+                            return results.into_iter();
+                        }
+
+                        let augmented_components: Vec<&str> = [
+                            &components[.. idx],
+                            [word.as_str()].as_slice(),
+                            &components[idx ..]
+                        ].concat();
+
+                        let augmented_domain = augmented_components.join(".");
+
+                        if seen.insert(augmented_domain.clone()) {
+                            results.push(augmented_domain);
+                            next_level.push(augmented_components);
+                        }
+                    }
+                }
+            }
+
+            level = next_level;
+        }
+
+        results.into_iter()
+    }
+}
+
+/// Returns the word along with every variant of it with one number shifted by up to `offsets`.
+fn number_variants(word: &str, offsets: u64) -> Vec<String> {
+    let mut variants = vec![word.to_string()];
+
+    if offsets == 0 {
+        return variants;
+    }
+
+    let segments = split_segments(word);
+
+    for (idx, segment) in segments.iter().enumerate() {
+        let number: u64 = match segment.parse() {
+            Ok(number) => number,
+            Err(_) => continue
+        };
+
+        let shifted = (1 ..= offsets)
+            .flat_map(|offset| [number.checked_sub(offset), number.checked_add(offset)])
+            .flatten();
+
+        // zero padded numbers like `01` keep their width
+        let width = match segment.starts_with('0') {
+            true => segment.len(),
+            false => 0
+        };
+
+        for new_number in shifted {
+            let new_number = format!("{:0width$}", new_number, width = width);
+
+            let variant: String = [
+                &segments[.. idx],
+                [new_number.as_str()].as_slice(),
+                &segments[idx + 1 ..]
+            ].concat().concat();
+
+            variants.push(variant);
+        }
+    }
+
+    variants
+}
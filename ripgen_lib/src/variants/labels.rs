@@ -0,0 +1,75 @@
+use crate::{DomainComponents, WordlistIterator};
+
+/// Removes one subdomain label at a time (e.g. `a.b.c.example.com` -> `b.c.example.com`,
+/// `a.c.example.com`, `a.b.example.com`).
+///
+/// Domains with a single subdomain label are skipped as removing it would only produce the root.
+pub fn label_deletion_transform<'domain>(
+    domain_components: &'domain DomainComponents,
+    _: WordlistIterator<'domain>,
+) -> impl Iterator<Item = String> + 'domain {
+    let subdomains = domain_components.subdomains();
+    let deletable = if subdomains.len() > 1 { subdomains.len() } else { 0 };
+
+    (0 .. deletable)
+        .map(move |idx| {
+            let new_sub: Vec<&str> = [
+                &subdomains[.. idx],
+                &subdomains[idx + 1 ..],
+                [domain_components.root()].as_slice()
+            ].concat();
+
+            new_sub.join(".")
+        })
+}
+
+/// Swaps every pair of adjacent subdomain labels (`dev.api` <-> `api.dev`) and moves every label
+/// to the front of the subdomains.
+///
+/// ```
+/// # use ripgen_lib::RipGenManager;
+/// # use ripgen_lib::variants::label_reorder_transform;
+/// let domains = vec!["a.b.c.example.com"];
+/// let manager = RipGenManager::new(domains.into_iter(), std::iter::empty(), &|_| true)
+///     .expect("Failed to parse domains.");
+///
+/// let mut output: Vec<String> = manager
+///     .transform(label_reorder_transform)
+///     .collect();
+/// output.sort();
+///
+/// assert_eq!(output, vec!["a.c.b.example.com", "b.a.c.example.com", "c.a.b.example.com"]);
+/// ```
+pub fn label_reorder_transform<'domain>(
+    domain_components: &'domain DomainComponents,
+    _: WordlistIterator<'domain>,
+) -> impl Iterator<Item = String> + 'domain {
+    let subdomains = domain_components.subdomains();
+    let root = domain_components.root();
+
+    let swaps = (1 .. subdomains.len())
+        .filter(move |idx| subdomains[idx - 1] != subdomains[*idx])
+        .map(move |idx| {
+            let mut new_sub: Vec<&str> = subdomains.to_vec();
+            new_sub.swap(idx - 1, idx);
+            new_sub.push(root);
+
+            new_sub.join(".")
+        });
+
+    // moving the second label to the front is the same as the first swap, so start at the third
+    let moves = (2 .. subdomains.len())
+        .filter(move |idx| subdomains[0] != subdomains[*idx])
+        .map(move |idx| {
+            let new_sub: Vec<&str> = [
+                [subdomains[idx]].as_slice(),
+                &subdomains[.. idx],
+                &subdomains[idx + 1 ..],
+                [root].as_slice()
+            ].concat();
+
+            new_sub.join(".")
+        });
+
+    swaps.chain(moves)
+}
@@ -0,0 +1,11 @@
+mod roots;
+mod labels;
+mod tokens;
+mod separators;
+mod depth;
+
+pub use roots::{root_swap_transform, suffix_swap_transform};
+pub use labels::{label_deletion_transform, label_reorder_transform};
+pub use tokens::token_swap_transform;
+pub use separators::separator_transform;
+pub use depth::depth_permute_transform;
@@ -0,0 +1,94 @@
+use fxhash::FxHashSet;
+use crate::{DomainComponents, WordlistIterator};
+
+/// Moves the subdomains of every domain onto each of the provided `roots`. Roots given more than
+/// once are only used once.
+///
+/// Passing in [RipGenManager::roots](crate::RipGenManager::roots) tries the names discovered under one
+/// root against every other root seen in the input.
+///
+/// ```
+/// # use ripgen_lib::RipGenManager;
+/// # use ripgen_lib::variants::root_swap_transform;
+/// let domains = vec!["api.example.com", "www.example.net"];
+/// let manager = RipGenManager::new(domains.into_iter(), std::iter::empty(), &|_| true)
+///     .expect("Failed to parse domains.");
+///
+/// let mut output: Vec<String> = manager
+///     .transform(root_swap_transform(manager.roots()))
+///     .collect();
+/// output.sort();
+///
+/// assert_eq!(output, vec!["api.example.net", "www.example.com"]);
+///
+/// let mut output: Vec<String> = manager
+///     .transform(root_swap_transform(manager.roots().into_iter().chain(["example.net"])))
+///     .collect();
+/// output.sort();
+///
+/// assert_eq!(output, vec!["api.example.net", "www.example.com"]);
+/// ```
+pub fn root_swap_transform<R: Into<String>>(
+    roots: impl IntoIterator<Item = R>
+) -> impl Fn(&DomainComponents, WordlistIterator) -> std::vec::IntoIter<String> {
+    let mut seen: FxHashSet<String> = FxHashSet::default();
+    let roots: Vec<String> = roots
+        .into_iter()
+        .map(|root| root.into().trim_matches('.').to_string())
+        .filter(|root| !root.is_empty() && seen.insert(root.clone()))
+        .collect();
+
+    move |domain_components: &DomainComponents, _: WordlistIterator| -> std::vec::IntoIter<String> {
+        // there's nothing to move if this is already a root
+        if domain_components.count() < 2 {
+            return Vec::new().into_iter();
+        }
+
+        let subdomain_string: String = domain_components.subdomains().join(".");
+
+        roots
+            .iter()
+            .filter(|root| root.as_str() != domain_components.root())
+            .map(|root| format!("{subdomain_string}.{root}"))
+            .collect::<Vec<String>>()
+            .into_iter()
+    }
+}
+
+/// Keeps the registrable name of every domain but replaces its public suffix with each of the
+/// provided `suffixes` (e.g. `www.example.com` -> `www.example.co.uk`). Suffixes given more than
+/// once are only used once.
+pub fn suffix_swap_transform<S: Into<String>>(
+    suffixes: impl IntoIterator<Item = S>
+) -> impl Fn(&DomainComponents, WordlistIterator) -> std::vec::IntoIter<String> {
+    let mut seen: FxHashSet<String> = FxHashSet::default();
+    let suffixes: Vec<String> = suffixes
+        .into_iter()
+        .map(|suffix| suffix.into().trim_matches('.').to_string())
+        .filter(|suffix| !suffix.is_empty() && seen.insert(suffix.clone()))
+        .collect();
+
+    move |domain_components: &DomainComponents, _: WordlistIterator| -> std::vec::IntoIter<String> {
+        let registrable_name = domain_components
+            .root()
+            .strip_suffix(domain_components.suffix())
+            .and_then(|name| name.strip_suffix('.'));
+
+        // the root is entirely a public suffix so there is no name to carry over
+        let registrable_name = match registrable_name {
+            Some(name) if !name.is_empty() => name,
+            _ => return Vec::new().into_iter()
+        };
+
+        let prefix: String = [domain_components.subdomains(), [registrable_name].as_slice()]
+            .concat()
+            .join(".");
+
+        suffixes
+            .iter()
+            .filter(|suffix| suffix.as_str() != domain_components.suffix())
+            .map(|suffix| format!("{prefix}.{suffix}"))
+            .collect::<Vec<String>>()
+            .into_iter()
+    }
+}
@@ -0,0 +1,93 @@
+use fxhash::FxHashSet;
+use crate::{DomainComponents, WordlistIterator};
+
+/// Varies the separators within the subdomains of a name.
+///
+/// Every dash in a label is removed (`api-dev` -> `apidev`) and promoted to a label boundary
+/// (`api-dev` -> `api.dev`), one dash at a time. Every pair of adjacent labels is joined with a dash
+/// and with nothing (`api.dev` -> `api-dev`, `apidev`).
+///
+/// Variants that would leave an empty label or one starting or ending with a dash are skipped, and
+/// each variant is only produced once for a domain.
+///
+/// ```
+/// # use ripgen_lib::RipGenManager;
+/// # use ripgen_lib::variants::separator_transform;
+/// let domains = vec!["api-dev.eu.example.com"];
+/// let manager = RipGenManager::new(domains.into_iter(), std::iter::empty(), &|_| true)
+///     .expect("Failed to parse domains.");
+///
+/// let mut output: Vec<String> = manager
+///     .transform(separator_transform)
+///     .collect();
+/// output.sort();
+///
+/// assert_eq!(output, vec![
+///     "api-dev-eu.example.com",
+///     "api-deveu.example.com",
+///     "api.dev.eu.example.com",
+///     "apidev.eu.example.com"
+/// ]);
+///
+/// let domains = vec!["a--b.example.com"];
+/// let manager = RipGenManager::new(domains.into_iter(), std::iter::empty(), &|_| true)
+///     .expect("Failed to parse domains.");
+///
+/// let output: Vec<String> = manager
+///     .transform(separator_transform)
+///     .collect();
+///
+/// assert_eq!(output, vec!["a-b.example.com"]);
+/// ```
+pub fn separator_transform(
+    domain_components: &DomainComponents,
+    _: WordlistIterator
+) -> std::vec::IntoIter<String> {
+    let subdomains = domain_components.subdomains();
+    let mut seen: FxHashSet<String> = FxHashSet::default();
+    let mut results = Vec::new();
+    let mut push = |idx: usize, count: usize, replacement: String| {
+        if replacement.split('.').all(is_valid_label) {
+            let variant = replace_labels(domain_components, idx, count, &replacement);
+
+            if seen.insert(variant.clone()) {
+                results.push(variant);
+            }
+        }
+    };
+
+    for (idx, label) in subdomains.iter().enumerate() {
+        for (dash_idx, _) in label.match_indices('-') {
+            let (left, right) = (&label[.. dash_idx], &label[dash_idx + 1 ..]);
+
+            push(idx, 1, format!("{left}{right}"));
+            push(idx, 1, format!("{left}.{right}"));
+        }
+    }
+
+    for idx in 1 .. subdomains.len() {
+        let (left, right) = (subdomains[idx - 1], subdomains[idx]);
+
+        push(idx - 1, 2, format!("{left}-{right}"));
+        push(idx - 1, 2, format!("{left}{right}"));
+    }
+
+    results.into_iter()
+}
+
+fn is_valid_label(label: &str) -> bool {
+    !label.is_empty() && !label.starts_with('-') && !label.ends_with('-')
+}
+
+/// Replaces `count` labels starting at `idx` with `replacement` and returns the full name.
+fn replace_labels(domain_components: &DomainComponents, idx: usize, count: usize, replacement: &str) -> String {
+    let all = domain_components.all();
+
+    let new_sub: Vec<&str> = [
+        &all[.. idx],
+        [replacement].as_slice(),
+        &all[idx + count ..]
+    ].concat();
+
+    new_sub.join(".")
+}
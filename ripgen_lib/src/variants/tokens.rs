@@ -0,0 +1,64 @@
+use crate::{DomainComponents, WordlistIterator};
+use crate::words::{split_segments, is_token};
+
+/// Replaces a single token of a subdomain label with every other word in the wordlist.
+///
+/// Labels are split into tokens on `-` and runs of digits, and only whole tokens matching a
+/// wordlist entry are replaced. Unlike dnsgen's `swap_word_transform`, a word such as `api`
+/// never rewrites part of `rapid`, and only one occurrence is replaced at a time.
+///
+/// ```
+/// # use ripgen_lib::RipGenManager;
+/// # use ripgen_lib::variants::token_swap_transform;
+/// let domains = vec!["api-rapid2.example.com"];
+/// let words = vec!["api", "dev"];
+/// let manager = RipGenManager::new(domains.into_iter(), words.into_iter(), &|_| false)
+///     .expect("Failed to parse domains.");
+///
+/// let output: Vec<String> = manager
+///     .transform(token_swap_transform)
+///     .collect();
+///
+/// assert_eq!(output, vec!["dev-rapid2.example.com"]);
+/// ```
+pub fn token_swap_transform<'domain>(
+    domain_components: &'domain DomainComponents,
+    words: WordlistIterator<'domain>,
+) -> impl Iterator<Item = String> + 'domain {
+    let subdomains = domain_components.subdomains();
+    let mut replaceable: Vec<(String, &'domain str, String)> = Vec::new();
+
+    for (label_idx, label) in subdomains.iter().enumerate() {
+        let segments = split_segments(label);
+
+        for (segment_idx, segment) in segments.iter().enumerate() {
+            if !is_token(segment) || !words.clone().any(|word| word == segment) {
+                continue;
+            }
+
+            let before: String = subdomains[.. label_idx]
+                .iter()
+                .map(|label| format!("{label}."))
+                .chain(segments[.. segment_idx].iter().map(|segment| segment.to_string()))
+                .collect();
+
+            let after: String = segments[segment_idx + 1 ..]
+                .iter()
+                .map(|segment| segment.to_string())
+                .chain(subdomains[label_idx + 1 ..].iter().map(|label| format!(".{label}")))
+                .chain([format!(".{}", domain_components.root())])
+                .collect();
+
+            replaceable.push((before, segment, after));
+        }
+    }
+
+    replaceable
+        .into_iter()
+        .flat_map(move |(before, token, after)| {
+            words
+                .clone()
+                .filter(move |word| **word != token)
+                .map(move |word| format!("{before}{word}{after}"))
+        })
+}
@@ -0,0 +1,172 @@
+//! Parses wordlists whose entries carry an optional weight and tags.
+//!
+//! Each line holds a word, optionally followed by its weight and any number of `key=value` tags,
+//! separated by whitespace (e.g. `admin 0.9 env=any`). Weights must be above 0 and words without a
+//! weight have a weight of 1. Empty lines and lines starting with `#` are skipped, so plain
+//! wordlists parse as they are unless a line holds more than a single word.
+//!
+//! With the `builtin-wordlists` feature, a few general purpose wordlists are compiled in and
+//! available from [builtin].
+
+use crate::RipGenError;
+
+/// The names and contents of the built-in wordlists.
+#[cfg(feature = "builtin-wordlists")]
+pub const BUILTIN_WORDLISTS: &[(&str, &str)] = &[
+    ("common", include_str!("../wordlists/common.txt")),
+    ("env", include_str!("../wordlists/env.txt")),
+    ("cloud", include_str!("../wordlists/cloud.txt")),
+    ("dnsgen", include_str!("../wordlists/dnsgen.txt"))
+];
+
+/// Returns the built-in wordlist with the given name: `common` for common service and host names,
+/// `env` for deployment environments, `cloud` for cloud services and infrastructure or `dnsgen` for
+/// the words dnsgen uses when it isn't given a wordlist.
+///
+/// ```
+/// # use ripgen_lib::wordlist::{builtin, Wordlist, BUILTIN_WORDLISTS};
+/// let env = Wordlist::parse(builtin("env").expect("Missing built-in wordlist."))
+///     .expect("Failed to parse wordlist.");
+///
+/// assert!(env.words().any(|word| word == "staging"));
+/// assert!(builtin("missing").is_none());
+/// assert!(BUILTIN_WORDLISTS.iter().all(|(_, wordlist)| Wordlist::parse(wordlist).is_ok()));
+/// ```
+#[cfg(feature = "builtin-wordlists")]
+pub fn builtin(name: &str) -> Option<&'static str> {
+    BUILTIN_WORDLISTS
+        .iter()
+        .find(|(builtin_name, _)| *builtin_name == name)
+        .map(|(_, wordlist)| *wordlist)
+}
+
+/// A single wordlist entry.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WordEntry<'a> {
+    pub word: &'a str,
+    /// How likely the word is to be worth generating. Higher is better.
+    pub weight: f64,
+    pub tags: Vec<(&'a str, &'a str)>
+}
+
+impl<'a> WordEntry<'a> {
+    /// Returns the value of the tag with the given key.
+    pub fn tag(&self, key: &str) -> Option<&'a str> {
+        self.tags
+            .iter()
+            .find(|(tag_key, _)| *tag_key == key)
+            .map(|(_, value)| *value)
+    }
+
+    /// Returns whether the entry has the tag `key=value`.
+    pub fn has_tag(&self, key: &str, value: &str) -> bool {
+        self.tags.contains(&(key, value))
+    }
+
+    fn parse(line: &'a str) -> Result<Self, RipGenError> {
+        let invalid = || RipGenError::ErrorParsingWordlist(line.to_string());
+
+        let mut fields = line.split_whitespace();
+        let word = fields.next().ok_or_else(invalid)?;
+
+        let mut entry = Self {
+            word,
+            weight: 1.0,
+            tags: Vec::new()
+        };
+
+        for (position, field) in fields.enumerate() {
+            match field.split_once('=') {
+                Some((key, value)) => entry.tags.push((key, value)),
+                None if position == 0 => {
+                    entry.weight = field.parse().map_err(|_| invalid())?;
+
+                    // a weight of 0 would make every name with the word infinitely unlikely
+                    if !entry.weight.is_finite() || entry.weight <= 0.0 {
+                        return Err(invalid());
+                    }
+                },
+                None => return Err(invalid())
+            }
+        }
+
+        Ok(entry)
+    }
+}
+
+/// The entries of a wordlist, in the order they were listed.
+///
+/// ```
+/// # use ripgen_lib::wordlist::Wordlist;
+/// let wordlist = Wordlist::parse("admin 0.9 env=any\neu 0.4 kind=region\nus kind=region\n# comment\n")
+///     .expect("Failed to parse wordlist.");
+///
+/// assert_eq!(wordlist.top(2), vec!["us", "admin"]);
+/// assert_eq!(wordlist.tagged("kind", "region").collect::<Vec<&str>>(), vec!["eu", "us"]);
+/// assert_eq!(wordlist.weight("eu"), Some(0.4));
+/// assert!(Wordlist::parse("admin 0").is_err());
+/// assert!(Wordlist::parse("legacy admin").is_err());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Wordlist<'a> {
+    entries: Vec<WordEntry<'a>>
+}
+
+impl<'a> Wordlist<'a> {
+    /// Parses a wordlist, failing on the first line with an invalid weight.
+    pub fn parse(wordlist: &'a str) -> Result<Self, RipGenError> {
+        let entries = wordlist
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(WordEntry::parse)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { entries })
+    }
+
+    pub fn entries(&self) -> &[WordEntry<'a>] {
+        &self.entries
+    }
+
+    /// Returns every word in the order they were listed.
+    pub fn words(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.entries.iter().map(|entry| entry.word)
+    }
+
+    /// Returns the weight of a word, or `None` if it isn't listed. Words listed more than once use
+    /// their highest weight.
+    pub fn weight(&self, word: &str) -> Option<f64> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.word == word)
+            .map(|entry| entry.weight)
+            .reduce(f64::max)
+    }
+
+    /// Returns the `count` words with the highest weight, from highest to lowest. Words with the
+    /// same weight keep the order they were listed in.
+    pub fn top(&self, count: usize) -> Vec<&'a str> {
+        let mut entries: Vec<&WordEntry<'a>> = self.entries.iter().collect();
+        entries.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+
+        entries
+            .into_iter()
+            .take(count)
+            .map(|entry| entry.word)
+            .collect()
+    }
+
+    /// Returns the words with the tag `key=value`, in the order they were listed.
+    pub fn tagged<'s>(&'s self, key: &'s str, value: &'s str) -> impl Iterator<Item = &'a str> + 's {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.has_tag(key, value))
+            .map(|entry| entry.word)
+    }
+
+    /// Keeps only the entries for which `keep` returns true.
+    pub fn retain(&mut self, keep: impl Fn(&WordEntry<'a>) -> bool) {
+        self.entries.retain(keep);
+    }
+}
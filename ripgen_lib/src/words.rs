@@ -1,9 +1,104 @@
+use std::slice::Iter as SliceIter;
+use fxhash::{FxHashMap, FxHashSet};
 use crate::domain::DomainComponents;
 
+/// How words are extracted from the subdomain labels of the input domains. Whole labels are
+/// always extracted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Tokenizer {
+    /// Only whole labels are extracted.
+    Labels,
+    /// Labels are also split on dashes, like dnsgen does.
+    #[default]
+    Dashes,
+    /// Labels are also split into the tokens between dashes and runs of digits.
+    Segments
+}
+
+/// Which words each domain is permuted with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WordScope {
+    /// Every domain gets the words extracted from every input domain.
+    #[default]
+    Global,
+    /// Each domain only gets the words extracted from domains with the same root.
+    Root,
+    /// Each domain only gets the words extracted from domains in the same input group, see
+    /// [RipGenManagerBuilder::domain_group](crate::RipGenManagerBuilder::domain_group).
+    Group
+}
+
+/// A set of words that keeps the order they were first added in, or the order of their weight once
+/// [ordered](Self::order_by), which is the order transforms try them in.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct WordSet<'domain> {
+    order: Vec<&'domain str>,
+    members: FxHashSet<&'domain str>
+}
+
+impl<'domain> WordSet<'domain> {
+    /// Adds a word after every other word. Returns whether it's new.
+    pub(crate) fn insert(&mut self, word: &'domain str) -> bool {
+        let new = self.members.insert(word);
+
+        if new {
+            self.order.push(word);
+        }
+
+        new
+    }
+
+    pub(crate) fn iter(&self) -> SliceIter<'_, &'domain str> {
+        self.order.iter()
+    }
+
+    pub(crate) fn contains(&self, word: &str) -> bool {
+        self.members.contains(word)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Keeps only the words for which `keep` returns true, given the word's position.
+    pub(crate) fn retain_positions(&mut self, keep: impl Fn(usize) -> bool) {
+        *self = self.order
+            .iter()
+            .enumerate()
+            .filter(|(position, _)| keep(*position))
+            .map(|(_, word)| *word)
+            .collect();
+    }
+
+    /// Orders the words from the highest to the lowest weight. Words without a weight have a weight
+    /// of 1, and words with the same weight keep their order.
+    pub(crate) fn order_by(&mut self, weights: &FxHashMap<&'domain str, f64>) {
+        let weight = |word: &str| weights.get(word).copied().unwrap_or(1.0);
+
+        self.order.sort_by(|a, b| weight(b).total_cmp(&weight(a)));
+    }
+}
+
+impl<'domain> Extend<&'domain str> for WordSet<'domain> {
+    fn extend<T: IntoIterator<Item = &'domain str>>(&mut self, words: T) {
+        for word in words {
+            self.insert(word);
+        }
+    }
+}
+
+impl<'domain> FromIterator<&'domain str> for WordSet<'domain> {
+    fn from_iter<T: IntoIterator<Item = &'domain str>>(words: T) -> Self {
+        let mut set = Self::default();
+        set.extend(words);
+        set
+    }
+}
 
 pub(crate) fn extract_words<'iter, 'domain>(
     domain_components: impl Iterator<Item = &'iter DomainComponents<'domain>> + 'iter,
     filter_function: &'iter impl Fn(&&str) -> bool,
+    tokenizer: Tokenizer
 ) -> impl Iterator<Item = &'domain str> + 'iter
     where
         'domain: 'iter
@@ -12,7 +107,13 @@ pub(crate) fn extract_words<'iter, 'domain>(
         .flat_map(move |domain| {
             let augments = domain
                 .subdomains_iter()
-                .flat_map(|elem| elem.split('-'));
+                .flat_map(move |elem| -> Vec<&'domain str> {
+                    match tokenizer {
+                        Tokenizer::Labels => Vec::new(),
+                        Tokenizer::Dashes => elem.split('-').collect(),
+                        Tokenizer::Segments => split_segments(elem).into_iter().filter(|segment| is_token(segment)).collect()
+                    }
+                });
 
             domain
                 .subdomains_iter().copied()
@@ -20,3 +121,53 @@ pub(crate) fn extract_words<'iter, 'domain>(
                 .filter(filter_function)
         })
 }
+
+/// Splits a label into alternating tokens and separators (dashes and digits) such that
+/// concatenating the segments returns the original label.
+pub(crate) fn split_segments(label: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut prev_class = None;
+
+    for (idx, ch) in label.char_indices() {
+        let class = Some(CharClass::from(ch));
+
+        if prev_class.is_some() && prev_class != class {
+            segments.push(&label[start .. idx]);
+            start = idx;
+        }
+
+        prev_class = class;
+    }
+
+    if start < label.len() {
+        segments.push(&label[start ..]);
+    }
+
+    segments
+}
+
+/// Returns whether the segment is a token rather than a separator.
+pub(crate) fn is_token(segment: &str) -> bool {
+    segment
+        .chars()
+        .next()
+        .is_some_and(|ch| CharClass::from(ch) == CharClass::Token)
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum CharClass {
+    Token,
+    Dash,
+    Digit
+}
+
+impl From<char> for CharClass {
+    fn from(ch: char) -> Self {
+        match ch {
+            '-' => CharClass::Dash,
+            ch if ch.is_ascii_digit() => CharClass::Digit,
+            _ => CharClass::Token
+        }
+    }
+}